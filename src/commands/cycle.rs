@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::{
+    data::algorithm::{
+        cycle,
+        limits::{SearchLimits, SearchResult},
+    },
+    error::Result,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct CycleArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Page the cycle has to pass through
+    #[arg(short, long)]
+    page: String,
+
+    /// Maximum number of links in the cycle
+    #[arg(long)]
+    max_depth: Option<u32>,
+
+    /// Give up after this many milliseconds
+    #[arg(long)]
+    timeout_ms: Option<u64>,
+}
+
+impl ArgExecutor for CycleArgs {
+    fn execute(&self) -> Result<()> {
+        cycle_cmd(self)
+    }
+}
+
+fn cycle_cmd(args: &CycleArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
+
+    let page = match db.pages.resolve_by_title(&args.page.replace(' ', "_")) {
+        Some(page) => page,
+        None => return Err("Page not found".into()),
+    };
+
+    // a page alone in its component can only return to itself by linking to itself
+    if let Some(components) = &db.components {
+        let self_loop = db
+            .links
+            .get(page.id)
+            .is_some_and(|targets| targets.contains(&page.id));
+        if components.component_size(page.id).unwrap_or(1) == 1 && !self_loop {
+            println!("😔 No cycle through {} exists", page.title);
+            return Ok(());
+        }
+    }
+
+    let limits = SearchLimits {
+        max_depth: args.max_depth,
+        timeout: args.timeout_ms.map(Duration::from_millis),
+        ..Default::default()
+    };
+
+    let time_before = std::time::Instant::now();
+    let result = cycle::shortest_cycle(page.id, &db.links, &limits);
+    let time = time_before.elapsed().as_millis();
+
+    match result {
+        SearchResult::Found(path) => {
+            println!(
+                "🎉 Shortest cycle with {} links found in {time}ms",
+                path.len() - 1
+            );
+            let titles = path
+                .into_iter()
+                .map(|id| db.pages.id_to_name(id).unwrap_or("?"))
+                .collect::<Vec<_>>();
+            println!("\t{}", titles.join(" > "));
+        }
+        SearchResult::NoPath => println!("😔 No cycle through {} exists", page.title),
+        SearchResult::LimitReached(_) => {
+            println!("⏱️ No cycle found within the limits ({time}ms)")
+        }
+        SearchResult::Cancelled => println!("🛑 Search cancelled after {time}ms"),
+    }
+
+    Ok(())
+}
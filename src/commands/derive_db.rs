@@ -1,17 +1,60 @@
-use clap::Args;
+use std::path::Path;
+
+use clap::{Args, ValueEnum};
 use log::info;
+use regex::Regex;
 
 use crate::{
     data::{
-        database::Database,
-        maps::{link_map::LinkMap, page_map::PageMap},
+        database::{Database, Format, Source},
+        maps::{
+            component_map::ComponentMap,
+            landmark_map::{LandmarkMap, LandmarkSelection},
+            link_map::LinkMap,
+            page_map::PageMap,
+        },
         parsers::{links, pages, redirects},
     },
+    error::{Error, Result},
     indication::ProgressBuilder,
 };
 
 use super::ArgExecutor;
 
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum LandmarkSelectionArg {
+    /// Pages with the most links
+    Degree,
+    /// Pages far away from each other
+    Farthest,
+}
+
+impl From<LandmarkSelectionArg> for LandmarkSelection {
+    fn from(arg: LandmarkSelectionArg) -> Self {
+        match arg {
+            LandmarkSelectionArg::Degree => LandmarkSelection::Degree,
+            LandmarkSelectionArg::Farthest => LandmarkSelection::Farthest,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum FormatArg {
+    /// Deserialized completely when loading
+    Cbor,
+    /// Memory-mapped and queried in place, loads instantly and is shared between processes
+    Mapped,
+}
+
+impl From<FormatArg> for Format {
+    fn from(arg: FormatArg) -> Self {
+        match arg {
+            FormatArg::Cbor => Format::Cbor,
+            FormatArg::Mapped => Format::Mapped,
+        }
+    }
+}
+
 #[derive(Args, Debug, Clone)]
 #[group()]
 pub struct DeriveDbArgs {
@@ -31,18 +74,86 @@ pub struct DeriveDbArgs {
     #[arg(short, long)]
     output: String,
 
+    /// Fail instead of replacing the output file if it already exists
+    #[arg(long)]
+    no_overwrite: bool,
+
     /// Number of threads to use
     #[arg(short, long, default_value = "2")]
     threads: i32,
+
+    /// Also store backlinks (reverse links), needed for backward searches but increases the database size
+    #[arg(short, long)]
+    backlinks: bool,
+
+    /// Number of landmark pages to precompute distances for, enables faster goal-directed searches
+    #[arg(long, default_value = "0")]
+    landmarks: usize,
+
+    /// How to choose the landmark pages
+    #[arg(long, value_enum, default_value = "farthest")]
+    landmark_selection: LandmarkSelectionArg,
+
+    /// Compute strongly connected components, so unreachable pages are detected without a search
+    #[arg(long)]
+    components: bool,
+
+    /// File format of the database
+    #[arg(long, value_enum, default_value = "cbor")]
+    format: FormatArg,
+
+    /// Compress the database with zstd at the given level (1-22, default 3 if no level is given), only for the cbor format
+    #[arg(long, num_args = 0..=1, default_missing_value = "3", value_parser = clap::value_parser!(i32).range(1..=22))]
+    compress: Option<i32>,
+
+    /// Name of the wiki stored in the database, detected from dump file names like dewiki-20240501-page.sql
+    #[arg(long)]
+    wiki: Option<String>,
+
+    /// Date of the dump stored in the database, detected like --wiki
+    #[arg(long)]
+    dump_date: Option<String>,
+}
+
+/// Wiki name and dump date from the name of a dump file like `dewiki-20240501-page.sql`
+pub fn detect_source(path: &str) -> Source {
+    let name = Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let re = Regex::new(r"^([a-z0-9_]+)-([0-9]{8}|latest)-").expect("Invalid regex");
+
+    match re.captures(&name) {
+        Some(cap) => Source {
+            wiki: Some(cap[1].to_string()),
+            dump_date: Some(cap[2].to_string()),
+        },
+        None => Source::default(),
+    }
 }
 
 impl ArgExecutor for DeriveDbArgs {
-    fn execute(&self) {
-        derive_db_command(self.clone());
+    fn execute(&self) -> Result<()> {
+        derive_db_command(self.clone())
     }
 }
 
-fn derive_db_command(args: DeriveDbArgs) {
+fn derive_db_command(args: DeriveDbArgs) -> Result<()> {
+    if args.compress.is_some() && matches!(args.format, FormatArg::Mapped) {
+        return Err("Mapped databases can not be compressed".into());
+    }
+
+    // checked again when writing, but fail before spending time on parsing
+    if args.no_overwrite && Path::new(&args.output).exists() {
+        return Err(Error::Input(format!("{} already exists", args.output)));
+    }
+
+    let detected = detect_source(&args.page_sql);
+    let source = Source {
+        wiki: args.wiki.or(detected.wiki),
+        dump_date: args.dump_date.or(detected.dump_date),
+    };
+
     let (page_sql, redirect_sql, pagelinks_sql, output, threads) = (
         args.page_sql,
         args.redirect_sql,
@@ -51,23 +162,34 @@ fn derive_db_command(args: DeriveDbArgs) {
         args.threads,
     );
 
+    // landmarks need backlinks to compute the distances to the landmarks
+    let build_backlinks = args.backlinks || args.landmarks > 0;
+    let build_landmarks = args.landmarks > 0;
+
+    let steps = 6 + build_backlinks as u8 + build_landmarks as u8 + args.components as u8;
+    let mut step = 5;
+    let mut next_step = || {
+        step += 1;
+        step
+    };
+
     let (pages, redirects) = {
         let pages = pages::read_and_parse_pages(
             page_sql,
             threads,
             ProgressBuilder::new()
-                .with_steps(1, 6)
+                .with_steps(1, steps)
                 .with_message("Loading pages...")
                 .with_finish_message("Pages loaded"),
-        );
+        )?;
         let redirects = redirects::read_and_parse_redirects(
             redirect_sql,
             threads,
             ProgressBuilder::new()
-                .with_steps(2, 6)
+                .with_steps(2, steps)
                 .with_message("Loading redirects...")
                 .with_finish_message("Redirects loaded"),
-        );
+        )?;
 
         (pages, redirects)
     };
@@ -82,7 +204,7 @@ fn derive_db_command(args: DeriveDbArgs) {
         pages,
         redirects,
         ProgressBuilder::new()
-            .with_steps(3, 6)
+            .with_steps(3, steps)
             .with_message("Remapping pages...")
             .with_finish_message("Pages remapped"),
     );
@@ -92,28 +214,92 @@ fn derive_db_command(args: DeriveDbArgs) {
         threads,
         &lookup,
         ProgressBuilder::new()
-            .with_steps(4, 6)
+            .with_steps(4, steps)
             .with_message("Loading links...")
             .with_finish_message("Links loaded"),
-    );
+    )?;
 
-    let links = LinkMap::new_with_progress(
+    let mut links = LinkMap::new_with_progress(
         links,
         ProgressBuilder::new()
-            .with_steps(5, 6)
+            .with_steps(5, steps)
             .with_message("Remapping links...")
             .with_finish_message("Links remapped"),
     );
 
+    if build_backlinks {
+        links.build_backlinks(
+            ProgressBuilder::new()
+                .with_steps(next_step(), steps)
+                .with_message("Building backlinks...")
+                .with_finish_message("Backlinks built"),
+        );
+    }
+
+    let landmarks = build_landmarks.then(|| {
+        LandmarkMap::new_with_progress(
+            &links,
+            args.landmarks,
+            args.landmark_selection.into(),
+            ProgressBuilder::new()
+                .with_steps(next_step(), steps)
+                .with_message("Computing landmark distances...")
+                .with_finish_message("Landmark distances computed"),
+        )
+    });
+
+    if !args.backlinks {
+        links.remove_backlinks();
+    }
+
+    let components = args.components.then(|| {
+        ComponentMap::new_with_progress(
+            &links,
+            ProgressBuilder::new()
+                .with_steps(next_step(), steps)
+                .with_message("Computing components...")
+                .with_finish_message("Components computed"),
+        )
+    });
+
+    if let Some(components) = &components {
+        info!(
+            "Got {} components, the largest has {} pages",
+            components.len(),
+            components.largest()
+        );
+    }
+
     {
         let spinner = ProgressBuilder::spinner()
             .with_message("Serializing and writing file")
-            .with_steps(6, 6)
+            .with_steps(next_step(), steps)
             .with_finish_message("Serialized and written to file")
             .build();
         spinner.enable_background();
-        Database::new(links, lookup).to_file(output.as_str());
+
+        let mut db = Database::new(links, lookup);
+        db.landmarks = landmarks;
+        db.components = components;
+        db.source = source;
+        db.format = args.format.into();
+        db.zstd_level = args.compress;
+        db.to_file(output.as_str(), !args.no_overwrite)?;
 
         spinner.finish();
     }
+
+    Ok(())
+}
+
+#[test]
+fn detect_dump_source() {
+    let source = detect_source("/dumps/dewiki-20240501-page.sql");
+    assert_eq!(source.wiki.as_deref(), Some("dewiki"));
+    assert_eq!(source.dump_date.as_deref(), Some("20240501"));
+
+    let source = detect_source("enwiki-latest-pagelinks.sql");
+    assert_eq!(source.dump_date.as_deref(), Some("latest"));
+
+    assert!(detect_source("page.sql").wiki.is_none());
 }
@@ -0,0 +1,176 @@
+use clap::Args;
+
+use crate::{
+    data::{database::Source, diff::DatabaseDiff},
+    error::Result,
+    indication::ProgressBuilder,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Database derived from the older dump
+    #[arg(long)]
+    old: String,
+
+    /// Database derived from the newer dump
+    #[arg(long)]
+    new: String,
+
+    /// Compare the shortest path between two pages, can be given multiple times
+    #[arg(long, num_args = 2, value_names = ["START", "TARGET"])]
+    path: Vec<String>,
+
+    /// Number of pages to list per kind of change
+    #[arg(short = 'n', long, default_value = "10")]
+    top: usize,
+
+    /// Print all changes as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+impl ArgExecutor for DiffArgs {
+    fn execute(&self) -> Result<()> {
+        diff_cmd(self)
+    }
+}
+
+fn diff_cmd(args: &DiffArgs) -> Result<()> {
+    let old = super::load_database(&args.old)?;
+    let new = super::load_database(&args.new)?;
+
+    let paths = args
+        .path
+        .chunks(2)
+        .map(|pair| (pair[0].replace(' ', "_"), pair[1].replace(' ', "_")))
+        .collect::<Vec<_>>();
+
+    let spinner = ProgressBuilder::spinner()
+        .with_message("🔍 Comparing databases")
+        .build();
+    spinner.enable_background();
+    let diff = DatabaseDiff::new(&old, &new, &paths);
+    spinner.finish();
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&diff).expect("Failed to serialize diff")
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Comparing {} with {}",
+        describe(&diff.old_source, &args.old),
+        describe(&diff.new_source, &args.new)
+    );
+    println!(
+        "Pages:     +{} added, -{} removed, {} renamed",
+        diff.added_pages.len(),
+        diff.removed_pages.len(),
+        diff.renamed_pages.len()
+    );
+    println!(
+        "Links:     +{} added, -{} removed on {} pages",
+        diff.added_links,
+        diff.removed_links,
+        diff.link_changes.len()
+    );
+    println!("Redirects: {} changed", diff.redirect_changes.len());
+
+    let more = |shown: usize, total: usize| {
+        if total > shown {
+            println!("\t... and {} more", total - shown);
+        }
+    };
+
+    if !diff.added_pages.is_empty() {
+        println!("\nAdded pages:");
+        for page in diff.added_pages.iter().take(args.top) {
+            println!("\t{}", page.title);
+        }
+        more(args.top, diff.added_pages.len());
+    }
+
+    if !diff.removed_pages.is_empty() {
+        println!("\nRemoved pages:");
+        for page in diff.removed_pages.iter().take(args.top) {
+            println!("\t{}", page.title);
+        }
+        more(args.top, diff.removed_pages.len());
+    }
+
+    if !diff.renamed_pages.is_empty() {
+        println!("\nRenamed pages:");
+        for rename in diff.renamed_pages.iter().take(args.top) {
+            println!("\t{} -> {}", rename.old_title, rename.new_title);
+        }
+        more(args.top, diff.renamed_pages.len());
+    }
+
+    if !diff.link_changes.is_empty() {
+        println!("\nPages with the most changed links:");
+        for changes in diff.link_changes.iter().take(args.top) {
+            println!(
+                "\t{:>+8} {:>+8} {}",
+                changes.added.len() as i64,
+                -(changes.removed.len() as i64),
+                changes.title
+            );
+        }
+        more(args.top, diff.link_changes.len());
+    }
+
+    if !diff.redirect_changes.is_empty() {
+        println!("\nChanged redirects:");
+        let target =
+            |target: &Option<String>| target.as_deref().unwrap_or("(no redirect)").to_string();
+        for change in diff.redirect_changes.iter().take(args.top) {
+            println!(
+                "\t{}: {} -> {}",
+                change.title,
+                target(&change.old_target),
+                target(&change.new_target)
+            );
+        }
+        more(args.top, diff.redirect_changes.len());
+    }
+
+    if !diff.paths.is_empty() {
+        println!("\nShortest paths:");
+    }
+    for path in &diff.paths {
+        let length = |path: &Option<Vec<String>>| {
+            path.as_ref()
+                .map_or("no path".to_string(), |path| match path.len() - 1 {
+                    1 => "1 link".to_string(),
+                    links => format!("{links} links"),
+                })
+        };
+        println!(
+            "\t{} -> {}: {} before, {} now",
+            path.start,
+            path.target,
+            length(&path.old),
+            length(&path.new)
+        );
+        for (name, titles) in [("before", &path.old), ("now", &path.new)] {
+            if let Some(titles) = titles {
+                println!("\t\t{name:<6} {}", titles.join(" > "));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Wiki and dump date of a database, or its path if they are unknown
+fn describe(source: &Source, path: &str) -> String {
+    match (&source.wiki, &source.dump_date) {
+        (Some(wiki), Some(date)) => format!("{wiki} {date}"),
+        _ => path.to_string(),
+    }
+}
@@ -0,0 +1,142 @@
+use clap::Args;
+use rand::{rngs::StdRng, seq::index, RngExt, SeedableRng};
+
+use crate::{
+    data::algorithm::{
+        bfs,
+        distance_sampling::{self, FarthestPair},
+    },
+    error::Result,
+    indication::ProgressBuilder,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct EstimateDistancesArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Number of randomly chosen pages to run a full BFS from
+    #[arg(short = 'n', long, default_value = "100")]
+    samples: usize,
+
+    /// Number of double sweeps for the diameter lower bound
+    #[arg(long, default_value = "4")]
+    sweeps: usize,
+
+    /// Number of longest found paths to print
+    #[arg(short, long, default_value = "3")]
+    examples: usize,
+
+    /// Seed for choosing the pages, random if not given
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+impl ArgExecutor for EstimateDistancesArgs {
+    fn execute(&self) -> Result<()> {
+        estimate_distances_cmd(self)
+    }
+}
+
+fn estimate_distances_cmd(args: &EstimateDistancesArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
+
+    let seed = args.seed.unwrap_or_else(|| rand::rng().random());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut pages = db
+        .pages
+        .iter()
+        .map(|(id, _)| id)
+        .filter(|&id| db.pages.id_to_redirect(id).is_none())
+        .collect::<Vec<_>>();
+    if pages.is_empty() {
+        println!("😔 The database contains no pages");
+        return Ok(());
+    }
+    // the map iteration order is random, the seed should choose the same pages
+    pages.sort_unstable();
+
+    let sample = |rng: &mut StdRng, amount: usize| {
+        index::sample(rng, pages.len(), amount.min(pages.len()))
+            .into_iter()
+            .map(|i| pages[i])
+            .collect::<Vec<_>>()
+    };
+
+    let sources = sample(&mut rng, args.samples);
+    let estimate = distance_sampling::estimate(
+        &sources,
+        &db.links,
+        ProgressBuilder::new()
+            .with_steps(1, 2)
+            .with_message("Running BFS from sampled pages...")
+            .with_finish_message("Sampled distances"),
+    );
+
+    let starts = sample(&mut rng, args.sweeps);
+    let progress = ProgressBuilder::new()
+        .with_steps(2, 2)
+        .with_message("Running double sweeps...")
+        .with_finish_message("Double sweeps done")
+        .with_len(starts.len() as u64)
+        .build();
+    let sweeps = starts
+        .into_iter()
+        .map(|start| {
+            let pair = distance_sampling::double_sweep(start, &db.links);
+            progress.inc(1);
+            pair
+        })
+        .collect::<Vec<_>>();
+    progress.finish();
+
+    println!(
+        "Sampled {} pages (seed {seed}), {} of them link to other pages",
+        sources.len(),
+        estimate.sources
+    );
+    println!(
+        "Reachable pages:  {:.1} ± {:.1}",
+        estimate.reachable.mean, estimate.reachable.margin
+    );
+    println!(
+        "Average distance: {:.3} ± {:.3}",
+        estimate.average.mean, estimate.average.margin
+    );
+
+    println!("\nDistance distribution (95% confidence):");
+    println!("\t{:>8}  {:>18}", "distance", "share of pages");
+    for (i, share) in estimate.histogram.iter().enumerate() {
+        println!(
+            "\t{:>8}  {:>7.3}% ± {:.3}%",
+            i + 1,
+            share.mean * 100.0,
+            share.margin * 100.0
+        );
+    }
+
+    let mut longest = sweeps
+        .into_iter()
+        .chain(estimate.farthest)
+        .collect::<Vec<FarthestPair>>();
+    longest.sort_unstable_by_key(|pair| (std::cmp::Reverse(pair.distance), pair.from, pair.to));
+    longest.dedup();
+
+    let diameter = longest.first().map(|pair| pair.distance).unwrap_or(0);
+    println!("\nDiameter: at least {diameter}");
+
+    let name = |id: i32| db.pages.id_to_name(id).unwrap_or("?");
+    for pair in longest.iter().take(args.examples) {
+        let Some(path) = bfs::find_shortest_path(pair.from, pair.to, &db.links) else {
+            continue;
+        };
+        let titles = path.into_iter().map(name).collect::<Vec<_>>();
+        println!("\t{}: {}", pair.distance, titles.join(" -> "));
+    }
+
+    Ok(())
+}
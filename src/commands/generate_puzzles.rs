@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use clap::Args;
+use rand::{rngs::StdRng, RngExt, SeedableRng};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::{
+    data::algorithm::puzzles,
+    error::{Error, Result},
+    indication::ProgressBuilder,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct GeneratePuzzlesArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Number of puzzles to generate
+    #[arg(short = 'n', long, default_value = "10")]
+    count: usize,
+
+    /// Number of links on the shortest path between start and target
+    #[arg(long, default_value = "4")]
+    distance: u32,
+
+    /// Minimum length of the wikitext of start and target in bytes
+    #[arg(long, default_value = "0")]
+    min_length: u32,
+
+    /// Minimum number of pages linking to start and target
+    #[arg(long, default_value = "0")]
+    min_in_degree: usize,
+
+    /// Minimum PageRank of start and target, needs ranks stored with the rank command
+    #[arg(long)]
+    min_rank: Option<f64>,
+
+    /// Exclude start and target pages whose title matches this regex
+    #[arg(long, default_value = r"_\((Begriffsklärung|disambiguation)\)$")]
+    exclude_matching: String,
+
+    /// Number of start pages to try before giving up, defaults to 100 per puzzle
+    #[arg(long)]
+    attempts: Option<usize>,
+
+    /// Seed for choosing the pages, random if not given
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Write the puzzles to this file instead of printing them
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PuzzleOutput<'a> {
+    start: &'a str,
+    target: &'a str,
+    distance: usize,
+    shortest_paths: u64,
+    solution: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct PuzzlesOutput<'a> {
+    seed: u64,
+    distance: u32,
+    puzzles: Vec<PuzzleOutput<'a>>,
+}
+
+impl ArgExecutor for GeneratePuzzlesArgs {
+    fn execute(&self) -> Result<()> {
+        generate_puzzles_cmd(self)
+    }
+}
+
+fn generate_puzzles_cmd(args: &GeneratePuzzlesArgs) -> Result<()> {
+    let exclude = match Regex::new(&args.exclude_matching) {
+        Ok(re) => re,
+        Err(e) => {
+            println!("Invalid pattern: {e}");
+            return Ok(());
+        }
+    };
+
+    let db = super::load_database(&args.db)?;
+
+    if args.min_rank.is_some() && db.ranks.is_none() {
+        return Err("Database has no ranks, store them with rank -s".into());
+    }
+
+    let mut in_degree: HashMap<i32, usize> = HashMap::new();
+    if args.min_in_degree > 0 {
+        for (_, targets) in db.links.iter() {
+            for &to in targets {
+                *in_degree.entry(to).or_default() += 1;
+            }
+        }
+    }
+
+    let mut pages = db
+        .pages
+        .iter()
+        .filter(|&(id, title)| {
+            db.pages.id_to_redirect(id).is_none()
+                && !exclude.is_match(title)
+                && db.pages.length(id).unwrap_or(0) >= args.min_length
+                && in_degree.get(&id).copied().unwrap_or(0) >= args.min_in_degree
+                && args.min_rank.is_none_or(|min| {
+                    db.ranks
+                        .as_ref()
+                        .and_then(|ranks| ranks.get(id))
+                        .is_some_and(|rank| rank >= min)
+                })
+        })
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    // the map iteration order is random, the seed should choose the same pages
+    pages.sort_unstable();
+
+    let seed = args.seed.unwrap_or_else(|| rand::rng().random());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let puzzles = puzzles::generate(
+        &pages,
+        args.distance,
+        args.count,
+        args.attempts.unwrap_or(args.count * 100),
+        &db.links,
+        &mut rng,
+        ProgressBuilder::new()
+            .with_steps(1, 1)
+            .with_message("Generating puzzles...")
+            .with_finish_message("Puzzles generated"),
+    );
+
+    // stderr, so the JSON on stdout stays valid
+    if puzzles.len() < args.count {
+        eprintln!(
+            "Only found {} of {} puzzles among {} eligible pages",
+            puzzles.len(),
+            args.count,
+            pages.len()
+        );
+    }
+
+    let title = |id: i32| db.pages.id_to_name(id).unwrap_or("?");
+    let output = PuzzlesOutput {
+        seed,
+        distance: args.distance,
+        puzzles: puzzles
+            .iter()
+            .map(|puzzle| PuzzleOutput {
+                start: title(puzzle.start),
+                target: title(puzzle.target),
+                distance: puzzle.solution.len() - 1,
+                shortest_paths: puzzle.shortest_paths,
+                solution: puzzle.solution.iter().map(|&id| title(id)).collect(),
+            })
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&output).expect("Failed to serialize puzzles");
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, json).map_err(Error::io(path))?;
+            println!("{} puzzles written to {path}", output.puzzles.len());
+        }
+        None => println!("{json}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use clap::Args;
+
+use crate::{
+    data::{
+        database::{Database, Format, FORMAT_VERSION},
+        maps::landmark_map::LandmarkSelection,
+    },
+    error::Result,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+}
+
+impl ArgExecutor for InfoArgs {
+    fn execute(&self) -> Result<()> {
+        info_cmd(self)
+    }
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD HH:MM:SS UTC`
+fn format_timestamp(seconds: u64) -> String {
+    let (days, time) = ((seconds / 86400) as i64, seconds % 86400);
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+fn info_cmd(args: &InfoArgs) -> Result<()> {
+    let (format, header) = match Database::read_header(&args.db) {
+        Ok(header) => header,
+        Err(message) => {
+            println!("{message}");
+            std::process::exit(1);
+        }
+    };
+
+    let unknown = || "unknown".to_string();
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+
+    let format = match format {
+        Format::Cbor => "cbor",
+        Format::Mapped => "mapped",
+    };
+
+    println!("Format:         {format} (version {FORMAT_VERSION})");
+    println!(
+        "Wiki:           {}",
+        header.source.wiki.unwrap_or_else(unknown)
+    );
+    println!(
+        "Dump date:      {}",
+        header.source.dump_date.unwrap_or_else(unknown)
+    );
+    println!("Created:        {}", format_timestamp(header.created_at));
+    println!("Pages:          {}", header.pages);
+    println!("Redirects:      {}", header.redirects);
+    println!("Links:          {}", header.links);
+    println!("Backlinks:      {}", yes_no(header.backlinks));
+    match header.landmark_selection {
+        Some(selection) => {
+            let selection = match selection {
+                LandmarkSelection::Degree => "degree",
+                LandmarkSelection::Farthest => "farthest",
+            };
+            println!("Landmarks:      {} ({selection})", header.landmarks);
+        }
+        None => println!("Landmarks:      {}", header.landmarks),
+    }
+    println!("Components:     {}", yes_no(header.components));
+    println!("Ranks:          {}", yes_no(header.ranks));
+
+    Ok(())
+}
+
+#[test]
+fn timestamps() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+    assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00 UTC");
+    assert_eq!(format_timestamp(1714567890), "2024-05-01 12:51:30 UTC");
+}
@@ -1,50 +1,36 @@
 use clap::Args;
 
 use crate::{
-    data::{
-        algorithm::bfs,
-        database::Database,
-        maps::page_map::{PageMap, PageMapResult},
-    },
-    indication::ProgressBuilder,
+    data::maps::page_map::{PageMap, PageMapResult},
+    error::Result,
 };
 
-use super::ArgExecutor;
+use super::{search, ArgExecutor};
 
 #[derive(Args, Debug)]
 pub struct InteractiveArgs {
     /// Database derived using derive-db command
     #[arg(short, long)]
     db: String,
+
+    #[command(flatten)]
+    search: search::SearchArgs,
 }
 
 impl ArgExecutor for InteractiveArgs {
-    fn execute(&self) {
-        interactive_cmd(self);
+    fn execute(&self) -> Result<()> {
+        interactive_cmd(self)
     }
 }
 
-fn interactive_cmd(args: &InteractiveArgs) {
-    let db = args.db.to_string();
-
-    let db = {
-        let spinner = ProgressBuilder::spinner()
-            .with_message("📝 Deserializing DB")
-            .build();
-        spinner.enable_background();
-        let data = Database::from_file(&db);
-        spinner.finish();
-        data
-    };
+fn interactive_cmd(args: &InteractiveArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
 
     println!(
         "Usage: Enter a start page and a target page to find the shortest path between them
 If you want to exit press ctrl+d or ctrl+c\n"
     );
 
-    let links = db.links;
-    let lookup = db.pages;
-
     fn page_input_loop(prompt: &str, pages: &PageMap) -> Option<PageMapResult> {
         loop {
             let input = inquire::Text::new(prompt).prompt();
@@ -64,12 +50,12 @@ If you want to exit press ctrl+d or ctrl+c\n"
     }
 
     loop {
-        let start = page_input_loop("Enter a start page name", &lookup);
+        let start = page_input_loop("Enter a start page name", &db.pages);
         if start.is_none() {
             break;
         }
 
-        let end = page_input_loop("Enter a target page name", &lookup);
+        let end = page_input_loop("Enter a target page name", &db.pages);
         if end.is_none() {
             break;
         }
@@ -77,30 +63,8 @@ If you want to exit press ctrl+d or ctrl+c\n"
         let start = start.unwrap();
         let end = end.unwrap();
 
-        let (path, time) = {
-            let spinner = ProgressBuilder::spinner()
-                .with_message("Searching for path")
-                .build();
-            spinner.enable_background();
-
-            let time_before = std::time::Instant::now();
-            let path = bfs::find_shortest_path(start.id, end.id, &links);
-            let time = time_before.elapsed().as_millis();
-
-            spinner.finish();
-
-            (path, time)
-        };
-
-        if path.is_none() {
-            println!("😔 No path found");
-            continue;
-        } else {
-            println!("🎉 Path found in {time}ms");
-            for page in path.unwrap() {
-                let page = lookup.id_to_name(page).unwrap();
-                println!("\t{}", page);
-            }
-        }
+        search::run_query(&args.search, start.id, end.id, &db)?;
     }
+
+    Ok(())
 }
@@ -0,0 +1,63 @@
+use clap::Args;
+
+use crate::error::Result;
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct LinksArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Page to list the links of
+    #[arg(short, long)]
+    page: String,
+
+    /// List pages linking to the page instead (needs a database derived with --backlinks)
+    #[arg(short, long)]
+    backlinks: bool,
+}
+
+impl ArgExecutor for LinksArgs {
+    fn execute(&self) -> Result<()> {
+        links_cmd(self)
+    }
+}
+
+fn links_cmd(args: &LinksArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
+
+    let page = match db.pages.resolve_by_title(&args.page.replace(' ', "_")) {
+        Some(page) => page,
+        None => return Err("Page not found".into()),
+    };
+
+    let links = if args.backlinks {
+        if !db.links.has_backlinks() {
+            return Err("Database was derived without backlinks".into());
+        }
+
+        println!(
+            "{} pages link to {}",
+            db.links.in_degree(page.id).unwrap_or(0),
+            page.title
+        );
+        db.links.backlinks(page.id)
+    } else {
+        println!(
+            "{} links to {} pages",
+            page.title,
+            db.links.out_degree(page.id)
+        );
+        db.links.get(page.id)
+    };
+
+    for &id in links.into_iter().flatten() {
+        if let Some(title) = db.pages.id_to_name(id) {
+            println!("\t{}", title);
+        }
+    }
+
+    Ok(())
+}
@@ -1,30 +1,136 @@
 use clap::Subcommand;
 
+use crate::{data::database::Database, error::Result, indication::ProgressBuilder};
+
+pub mod cycle;
 pub mod derive_db;
+pub mod diff;
+pub mod estimate_distances;
+pub mod generate_puzzles;
+pub mod info;
 pub mod interactive;
+pub mod links;
+pub mod path;
+pub mod rank;
+pub mod reach;
+pub mod related;
+pub mod search;
+pub mod stats;
+pub mod update;
+pub mod verify;
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
+    Cycle {
+        #[command(flatten)]
+        args: cycle::CycleArgs,
+    },
+
     DeriveDB {
         #[command(flatten)]
         args: derive_db::DeriveDbArgs,
     },
 
+    Diff {
+        #[command(flatten)]
+        args: diff::DiffArgs,
+    },
+
+    EstimateDistances {
+        #[command(flatten)]
+        args: estimate_distances::EstimateDistancesArgs,
+    },
+
+    GeneratePuzzles {
+        #[command(flatten)]
+        args: generate_puzzles::GeneratePuzzlesArgs,
+    },
+
+    Info {
+        #[command(flatten)]
+        args: info::InfoArgs,
+    },
+
     Interactive {
         #[command(flatten)]
         args: interactive::InteractiveArgs,
     },
+
+    Links {
+        #[command(flatten)]
+        args: links::LinksArgs,
+    },
+
+    Path {
+        #[command(flatten)]
+        args: path::PathArgs,
+    },
+
+    Reach {
+        #[command(flatten)]
+        args: reach::ReachArgs,
+    },
+
+    Rank {
+        #[command(flatten)]
+        args: rank::RankArgs,
+    },
+
+    Related {
+        #[command(flatten)]
+        args: related::RelatedArgs,
+    },
+
+    Stats {
+        #[command(flatten)]
+        args: stats::StatsArgs,
+    },
+
+    Update {
+        #[command(flatten)]
+        args: update::UpdateArgs,
+    },
+
+    Verify {
+        #[command(flatten)]
+        args: verify::VerifyArgs,
+    },
 }
 
 pub trait ArgExecutor {
-    fn execute(&self);
+    fn execute(&self) -> Result<()>;
 }
 
 impl Commands {
-    pub fn execute(&self) {
+    pub fn execute(&self) -> Result<()> {
         match self {
+            Commands::Cycle { args } => args.execute(),
             Commands::DeriveDB { args } => args.execute(),
+            Commands::Diff { args } => args.execute(),
+            Commands::EstimateDistances { args } => args.execute(),
+            Commands::GeneratePuzzles { args } => args.execute(),
+            Commands::Info { args } => args.execute(),
             Commands::Interactive { args } => args.execute(),
+            Commands::Links { args } => args.execute(),
+            Commands::Path { args } => args.execute(),
+            Commands::Reach { args } => args.execute(),
+            Commands::Rank { args } => args.execute(),
+            Commands::Related { args } => args.execute(),
+            Commands::Stats { args } => args.execute(),
+            Commands::Update { args } => args.execute(),
+            Commands::Verify { args } => args.execute(),
         }
     }
 }
+
+/// Loads a database derived using the derive-db command while showing a spinner
+pub fn load_database(path: &str) -> Result<Database> {
+    let spinner = ProgressBuilder::spinner()
+        .with_message("📝 Deserializing DB")
+        .build();
+    spinner.enable_background();
+    let data = Database::from_file(path);
+    spinner.finish();
+
+    data
+}
@@ -0,0 +1,42 @@
+use clap::Args;
+
+use crate::error::Result;
+
+use super::{search, ArgExecutor};
+
+#[derive(Args, Debug)]
+pub struct PathArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Start page
+    #[arg(short, long)]
+    start: String,
+
+    /// Target page
+    #[arg(short, long)]
+    target: String,
+
+    #[command(flatten)]
+    search: search::SearchArgs,
+}
+
+impl ArgExecutor for PathArgs {
+    fn execute(&self) -> Result<()> {
+        path_cmd(self)
+    }
+}
+
+fn path_cmd(args: &PathArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
+
+    let start = db.pages.resolve_by_title(&args.start.replace(' ', "_"));
+    let end = db.pages.resolve_by_title(&args.target.replace(' ', "_"));
+
+    match (start, end) {
+        (Some(start), Some(end)) => search::run_query(&args.search, start.id, end.id, &db),
+        (None, _) => Err("Start page not found".into()),
+        (_, None) => Err("Target page not found".into()),
+    }
+}
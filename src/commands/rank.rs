@@ -0,0 +1,112 @@
+use clap::Args;
+
+use crate::{
+    data::{
+        algorithm::pagerank::{self, PageRankOptions},
+        maps::{page_map::PageMap, rank_map::RankMap},
+    },
+    error::Result,
+    indication::ProgressBuilder,
+};
+
+use super::ArgExecutor;
+
+/// Longer redirect chains are treated as cycles
+const MAX_REDIRECTS: usize = 32;
+
+#[derive(Args, Debug)]
+pub struct RankArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Probability of following a link instead of jumping to a random page, between 0 and 1
+    #[arg(long, default_value = "0.85", value_parser = parse_damping)]
+    damping: f64,
+
+    /// Maximum number of iterations
+    #[arg(short, long, default_value = "100")]
+    iterations: usize,
+
+    /// Stop once the scores change less than this in total between iterations
+    #[arg(long, default_value = "1e-9")]
+    tolerance: f64,
+
+    /// Number of pages to print
+    #[arg(short = 'n', long, default_value = "20")]
+    top: usize,
+
+    /// Store the scores in the database
+    #[arg(short, long)]
+    store: bool,
+}
+
+impl ArgExecutor for RankArgs {
+    fn execute(&self) -> Result<()> {
+        rank_cmd(self)
+    }
+}
+
+fn parse_damping(value: &str) -> std::result::Result<f64, String> {
+    let damping = value.parse::<f64>().map_err(|e| e.to_string())?;
+    if !(0.0..1.0).contains(&damping) {
+        return Err("must be at least 0 and less than 1".to_string());
+    }
+    Ok(damping)
+}
+
+/// Page at the end of the redirect chain starting at `id`, `None` for redirect cycles
+fn final_page(pages: &PageMap, id: i32) -> Option<i32> {
+    let mut at = id;
+    for _ in 0..MAX_REDIRECTS {
+        match pages.id_to_redirect(at) {
+            Some(next) => at = next,
+            None => return Some(at),
+        }
+    }
+    None
+}
+
+fn rank_cmd(args: &RankArgs) -> Result<()> {
+    let mut db = super::load_database(&args.db)?;
+
+    let options = PageRankOptions {
+        damping: args.damping,
+        iterations: args.iterations,
+        tolerance: args.tolerance,
+    };
+
+    // links to redirects count for the page they lead to, redirects themselves get no rank
+    let (ranks, iterations) = pagerank::pagerank(
+        db.pages.iter().map(|(id, _)| id),
+        &db.links,
+        |id| final_page(&db.pages, id),
+        options,
+        ProgressBuilder::new()
+            .with_steps(1, 1)
+            .with_message("Computing PageRank...")
+            .with_finish_message("PageRank computed"),
+    );
+    let ranks = RankMap::new(ranks);
+
+    println!("PageRank after {iterations} iterations:");
+    for (i, (id, rank)) in ranks.top(args.top).into_iter().enumerate() {
+        let title = db.pages.id_to_name(id).unwrap_or("?");
+        println!("\t{:>4}. {:.8} {}", i + 1, rank, title);
+    }
+
+    if args.store {
+        let spinner = ProgressBuilder::spinner()
+            .with_message("Serializing and writing file")
+            .with_finish_message("Scores stored in database")
+            .build();
+        spinner.enable_background();
+
+        db.ranks = Some(ranks);
+        db.to_file(&args.db, true)?;
+
+        spinner.finish();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use clap::Args;
+
+use crate::{
+    data::algorithm::layers::{self, Direction},
+    error::Result,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct ReachArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Page to start from
+    #[arg(short, long)]
+    page: String,
+
+    /// Maximum number of clicks, unlimited if not given
+    #[arg(short = 'n', long)]
+    depth: Option<u32>,
+
+    /// Count the pages that can reach the page instead (needs a database derived with --backlinks)
+    #[arg(short, long)]
+    backward: bool,
+
+    /// Also print the titles of the pages per depth
+    #[arg(short, long)]
+    titles: bool,
+
+    /// Maximum number of titles to print per depth
+    #[arg(short, long, default_value = "50")]
+    max_titles: usize,
+}
+
+impl ArgExecutor for ReachArgs {
+    fn execute(&self) -> Result<()> {
+        reach_cmd(self)
+    }
+}
+
+fn reach_cmd(args: &ReachArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
+
+    let page = match db.pages.resolve_by_title(&args.page.replace(' ', "_")) {
+        Some(page) => page,
+        None => return Err("Page not found".into()),
+    };
+
+    let direction = if args.backward {
+        if !db.links.has_backlinks() {
+            return Err("Database was derived without backlinks".into());
+        }
+        Direction::Backward
+    } else {
+        Direction::Forward
+    };
+
+    let layers = layers::distance_layers(page.id, direction, args.depth, &db.links);
+
+    println!("{:>6} {:>10} {:>12}", "depth", "pages", "cumulative");
+    let mut cumulative = 0;
+    for (depth, layer) in layers.iter().enumerate() {
+        cumulative += layer.len();
+        println!("{:>6} {:>10} {:>12}", depth, layer.len(), cumulative);
+
+        if args.titles {
+            let mut titles = layer
+                .iter()
+                .filter_map(|&id| db.pages.id_to_name(id))
+                .collect::<Vec<_>>();
+            titles.sort_unstable();
+
+            for title in titles.iter().take(args.max_titles) {
+                println!("\t{}", title);
+            }
+            if titles.len() > args.max_titles {
+                println!("\t... and {} more", titles.len() - args.max_titles);
+            }
+        }
+    }
+
+    let eccentricity = layers.len() - 1;
+    let depth_limited = args.depth.is_some_and(|max| eccentricity == max as usize);
+    if depth_limited && layers::reaches_beyond(&layers, direction, &db.links) {
+        println!("Eccentricity: at least {eccentricity} (depth limit reached)");
+    } else {
+        println!("Eccentricity: {eccentricity}");
+    }
+
+    Ok(())
+}
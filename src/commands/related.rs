@@ -0,0 +1,105 @@
+use clap::{Args, ValueEnum};
+
+use crate::{
+    data::algorithm::related::{self, Relation, Similarity},
+    error::Result,
+};
+
+use super::ArgExecutor;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationArg {
+    /// Pages linked from the same pages
+    CoCitation,
+    /// Pages linking to the same pages
+    Coupling,
+}
+
+impl From<RelationArg> for Relation {
+    fn from(arg: RelationArg) -> Self {
+        match arg {
+            RelationArg::CoCitation => Relation::CoCitation,
+            RelationArg::Coupling => Relation::Coupling,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimilarityArg {
+    /// Share of common links among all links of both pages
+    Jaccard,
+    /// Common links weighted by how rare they are
+    AdamicAdar,
+}
+
+impl From<SimilarityArg> for Similarity {
+    fn from(arg: SimilarityArg) -> Self {
+        match arg {
+            SimilarityArg::Jaccard => Similarity::Jaccard,
+            SimilarityArg::AdamicAdar => Similarity::AdamicAdar,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct RelatedArgs {
+    /// Database derived using derive-db command with --backlinks
+    #[arg(short, long)]
+    db: String,
+
+    /// Page to find related pages for
+    #[arg(short, long)]
+    page: String,
+
+    /// Which shared links make pages related
+    #[arg(short, long, value_enum, default_value = "co-citation")]
+    relation: RelationArg,
+
+    /// How the shared links are scored
+    #[arg(short, long, value_enum, default_value = "jaccard")]
+    similarity: SimilarityArg,
+
+    /// Number of pages to print
+    #[arg(short = 'n', long, default_value = "20")]
+    top: usize,
+}
+
+impl ArgExecutor for RelatedArgs {
+    fn execute(&self) -> Result<()> {
+        related_cmd(self)
+    }
+}
+
+fn related_cmd(args: &RelatedArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
+
+    if !db.links.has_backlinks() {
+        return Err("Database was derived without backlinks".into());
+    }
+
+    let page = match db.pages.resolve_by_title(&args.page.replace(' ', "_")) {
+        Some(page) => page,
+        None => return Err("Page not found".into()),
+    };
+
+    let related = related::related(
+        page.id,
+        args.relation.into(),
+        args.similarity.into(),
+        args.top,
+        &db.links,
+    );
+
+    if related.is_empty() {
+        println!("😔 No page shares links with {}", page.title);
+        return Ok(());
+    }
+
+    println!("Pages related to {}:", page.title);
+    for (i, (id, score)) in related.into_iter().enumerate() {
+        let title = db.pages.id_to_name(id).unwrap_or("?");
+        println!("\t{:>4}. {:.6} {}", i + 1, score, title);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,414 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    time::Duration,
+};
+
+use clap::{Args, ValueEnum};
+use log::debug;
+use regex::Regex;
+
+use crate::{
+    data::{
+        algorithm::{
+            all_paths, alt, bfs, bidirectional,
+            constrained::{self, Constraints},
+            dijkstra::{self, EdgeWeight},
+            limits::{CancellationToken, Limit, SearchLimits, SearchResult},
+            parallel_bfs, yen,
+        },
+        database::Database,
+    },
+    error::{Error, Result},
+    indication::ProgressBuilder,
+};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Landmark search if the database has landmarks, bidirectional search if it has backlinks, otherwise BFS
+    Auto,
+    /// Breadth-first search from the start page
+    Bfs,
+    /// Breadth-first search from both ends, needs backlinks
+    Bidirectional,
+    /// A* search with landmark distance bounds, needs landmarks
+    Alt,
+    /// Breadth-first search from the start page, expanding each level on multiple threads
+    Parallel,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weight {
+    /// Every link costs the same, like the unweighted searches
+    Uniform,
+    /// Links to pages with many backlinks are cheaper, needs backlinks
+    InDegree,
+    /// Links to pages with a high PageRank are cheaper, needs ranks stored with the rank command
+    Pagerank,
+}
+
+// Options shared by all commands that search paths, a doc comment would become the about text
+// of the commands flattening them
+#[derive(Args, Debug, Clone)]
+pub struct SearchArgs {
+    /// Algorithm used for single shortest path searches
+    #[arg(long, value_enum, default_value = "auto")]
+    algorithm: Algorithm,
+
+    /// Find the path with the lowest total link cost instead of the fewest links (Dijkstra search)
+    #[arg(long, value_enum, conflicts_with_all = ["all_paths", "k", "via"])]
+    weight: Option<Weight>,
+
+    /// Number of threads used by the parallel algorithm, defaults to the number of cores
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// Maximum number of links on the path
+    #[arg(long, conflicts_with_all = ["all_paths", "k"])]
+    max_depth: Option<u32>,
+
+    /// Maximum number of pages to expand before giving up
+    #[arg(long, conflicts_with_all = ["all_paths", "k"])]
+    max_expanded: Option<u64>,
+
+    /// Give up after this many milliseconds
+    #[arg(long, conflicts_with_all = ["all_paths", "k"])]
+    timeout_ms: Option<u64>,
+
+    /// Find all shortest paths and display them as a graph
+    #[arg(short, long, conflicts_with = "k")]
+    all_paths: bool,
+
+    /// Maximum number of paths to list when using --all-paths
+    #[arg(short, long, default_value = "10")]
+    max_paths: usize,
+
+    /// Find the k shortest loopless paths, including longer ones
+    #[arg(short, long)]
+    k: Option<usize>,
+
+    /// Page the path must not pass through, can be given multiple times
+    #[arg(long, conflicts_with_all = ["all_paths", "k"])]
+    avoid: Vec<String>,
+
+    /// Avoid all pages whose title matches this regex, e.g. '^[0-9]{1,4}$' for year pages
+    #[arg(long, conflicts_with_all = ["all_paths", "k"])]
+    avoid_matching: Option<String>,
+
+    /// Page the path has to pass through, can be given multiple times (visited in the given order)
+    #[arg(long, conflicts_with_all = ["all_paths", "k"])]
+    via: Vec<String>,
+}
+
+impl SearchArgs {
+    /// Resolves the --avoid and --via options to page ids
+    fn constraints(&self, db: &Database) -> Result<Constraints> {
+        let resolve = |title: &String| {
+            db.pages
+                .resolve_by_title(&title.replace(' ', "_"))
+                .map(|page| page.id)
+                .ok_or_else(|| Error::Input(format!("Page not found: {title}")))
+        };
+
+        let mut avoid = self
+            .avoid
+            .iter()
+            .map(resolve)
+            .collect::<Result<HashSet<_>>>()?;
+
+        if let Some(pattern) = &self.avoid_matching {
+            let re =
+                Regex::new(pattern).map_err(|e| Error::Input(format!("Invalid pattern: {e}")))?;
+            avoid.extend(
+                db.pages
+                    .iter()
+                    .filter(|(_, title)| re.is_match(title))
+                    .map(|(id, _)| id),
+            );
+        }
+
+        let via = self.via.iter().map(resolve).collect::<Result<_>>()?;
+
+        Ok(Constraints { avoid, via })
+    }
+
+    /// Cost function selected by --weight
+    fn edge_weight<'a>(&self, db: &'a Database) -> Result<Option<EdgeWeight<'a>>> {
+        let Some(weight) = self.weight else {
+            return Ok(None);
+        };
+
+        let weight = match weight {
+            Weight::Uniform => EdgeWeight::Uniform,
+            Weight::InDegree if !db.links.has_backlinks() => {
+                return Err("Database was derived without backlinks".into())
+            }
+            Weight::InDegree => EdgeWeight::InverseInDegree(&db.links),
+            Weight::Pagerank => match &db.ranks {
+                Some(ranks) => EdgeWeight::PageRank(ranks),
+                None => return Err("Database has no ranks, store them with rank -s".into()),
+            },
+        };
+
+        Ok(Some(weight))
+    }
+
+    fn limits(&self, cancel: CancellationToken) -> SearchLimits {
+        SearchLimits {
+            max_depth: self.max_depth,
+            max_expanded: self.max_expanded,
+            timeout: self.timeout_ms.map(Duration::from_millis),
+            cancel: Some(cancel),
+        }
+    }
+}
+
+// whether a search that can be cancelled is running
+static SEARCHING: AtomicBool = AtomicBool::new(false);
+
+/// Token cancelled by ctrl+c while a search is running, ctrl+c exits as usual otherwise
+fn interrupt_token() -> CancellationToken {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+
+    TOKEN
+        .get_or_init(|| {
+            let token = CancellationToken::new();
+            let handler_token = token.clone();
+            ctrlc::set_handler(move || {
+                if SEARCHING.load(Ordering::SeqCst) {
+                    handler_token.cancel();
+                } else {
+                    std::process::exit(130);
+                }
+            })
+            .expect("Error setting ctrl+c handler");
+            token
+        })
+        .clone()
+}
+
+/// Runs `search` while showing a spinner, returns the result and the elapsed milliseconds
+fn timed<T>(message: &str, search: impl FnOnce() -> T) -> (T, u128) {
+    let spinner = ProgressBuilder::spinner().with_message(message).build();
+    spinner.enable_background();
+
+    let time_before = std::time::Instant::now();
+    let result = search();
+    let time = time_before.elapsed().as_millis();
+
+    spinner.finish();
+
+    (result, time)
+}
+
+/// Searches paths from `start` to `end` as configured by `args` and prints them
+pub fn run_query(args: &SearchArgs, start: i32, end: i32, db: &Database) -> Result<()> {
+    if let Some(components) = &db.components {
+        if !components.reachable(start, end) {
+            println!("😔 No path exists (target is not reachable from the start)");
+            return Ok(());
+        }
+    }
+
+    if args.all_paths {
+        print_all_paths(args, start, end, db);
+    } else if let Some(k) = args.k {
+        print_k_paths(k, start, end, db);
+    } else {
+        let constraints = args.constraints(db)?;
+
+        match args.edge_weight(db)? {
+            Some(weight) => print_weighted_path(args, start, end, &weight, &constraints, db),
+            None => print_path(args, start, end, &constraints, db)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn title(db: &Database, id: i32) -> &str {
+    db.pages.id_to_name(id).unwrap()
+}
+
+fn print_path(
+    args: &SearchArgs,
+    start: i32,
+    end: i32,
+    constraints: &Constraints,
+    db: &Database,
+) -> Result<()> {
+    let algorithm = match (args.algorithm, &db.landmarks) {
+        (Algorithm::Auto, Some(_)) => Algorithm::Alt,
+        (Algorithm::Auto, None) => Algorithm::Bidirectional,
+        (Algorithm::Alt, None) => return Err("Database was derived without landmarks".into()),
+        (Algorithm::Bidirectional, _) if !db.links.has_backlinks() => {
+            return Err("Database was derived without backlinks".into())
+        }
+        (algorithm, _) => algorithm,
+    };
+
+    let cancel = interrupt_token();
+    cancel.reset();
+    let limits = args.limits(cancel);
+
+    SEARCHING.store(true, Ordering::SeqCst);
+    let (result, time) = timed("Searching for path", || {
+        if !constraints.is_empty() {
+            return constrained::search(start, end, constraints, &db.links, &limits);
+        }
+
+        match (algorithm, &db.landmarks) {
+            (Algorithm::Alt, Some(landmarks)) => {
+                debug!("Using {} landmarks", landmarks.landmarks().len());
+                alt::search(start, end, &db.links, landmarks, &limits)
+            }
+            (Algorithm::Bidirectional, _) => bidirectional::search(start, end, &db.links, &limits),
+            (Algorithm::Parallel, _) => {
+                let threads = args
+                    .threads
+                    .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+                parallel_bfs::search(start, end, &db.links, threads, &limits)
+            }
+            _ => bfs::search(start, end, &db.links, &limits),
+        }
+    });
+    SEARCHING.store(false, Ordering::SeqCst);
+
+    print_result(result, time, db);
+
+    Ok(())
+}
+
+/// Prints the outcome of a single path search
+fn print_result(result: SearchResult, time: u128, db: &Database) {
+    match result {
+        SearchResult::Found(path) => {
+            println!("🎉 Path found in {time}ms");
+            for page in path {
+                println!("\t{}", title(db, page));
+            }
+        }
+        SearchResult::NoPath => println!("😔 No path exists"),
+        SearchResult::LimitReached(limit) => {
+            let limit = match limit {
+                Limit::Depth => "maximum depth",
+                Limit::Expanded => "maximum number of expanded pages",
+                Limit::Timeout => "timeout",
+            };
+            println!("⏱️ No path found within the limits ({limit} reached after {time}ms)");
+        }
+        SearchResult::Cancelled => println!("🛑 Search cancelled after {time}ms"),
+    }
+}
+
+fn print_weighted_path(
+    args: &SearchArgs,
+    start: i32,
+    end: i32,
+    weight: &EdgeWeight,
+    constraints: &Constraints,
+    db: &Database,
+) {
+    // like the unweighted search, the target is reachable even if it is avoided
+    let cost = |_, to| {
+        if to != end && constraints.avoid.contains(&to) {
+            f64::INFINITY
+        } else {
+            weight.cost(to)
+        }
+    };
+
+    let cancel = interrupt_token();
+    cancel.reset();
+    let limits = args.limits(cancel);
+
+    SEARCHING.store(true, Ordering::SeqCst);
+    let (result, time) = timed("Searching for path", || {
+        dijkstra::search(start, end, &db.links, cost, &limits)
+    });
+    SEARCHING.store(false, Ordering::SeqCst);
+
+    let total = match &result {
+        SearchResult::Found(path) => Some(dijkstra::path_cost(path, cost)),
+        _ => None,
+    };
+
+    print_result(result, time, db);
+
+    if let Some(total) = total {
+        println!("Total cost: {total:.6}");
+    }
+}
+
+fn print_k_paths(k: usize, start: i32, end: i32, db: &Database) {
+    let (paths, time) = timed("Searching for paths", || {
+        yen::k_shortest_paths(start, end, k, &db.links)
+    });
+
+    if paths.is_empty() {
+        println!("😔 No path found");
+        return;
+    }
+
+    println!("🎉 {} paths found in {time}ms", paths.len());
+    for (i, path) in paths.into_iter().enumerate() {
+        let length = path.len() - 1;
+        let path = path.into_iter().map(|id| title(db, id)).collect::<Vec<_>>();
+        println!("\t{:>3}. ({length}) {}", i + 1, path.join(" > "));
+    }
+}
+
+fn print_all_paths(args: &SearchArgs, start: i32, end: i32, db: &Database) {
+    let (paths, time) = timed("Searching for paths", || {
+        all_paths::find_all_shortest_paths(start, end, &db.links)
+    });
+
+    let paths = match paths {
+        Some(paths) => paths,
+        None => {
+            println!("😔 No path found");
+            return;
+        }
+    };
+
+    println!(
+        "🎉 {} shortest paths of length {} found in {time}ms",
+        paths.count(),
+        paths.length()
+    );
+
+    for (depth, level) in paths.levels().into_iter().enumerate() {
+        for (i, node) in level.into_iter().enumerate() {
+            let prefix = if i == 0 {
+                format!("[{depth}]")
+            } else {
+                String::new()
+            };
+
+            let predecessors = paths
+                .predecessors(node)
+                .iter()
+                .map(|&pred| title(db, pred))
+                .collect::<Vec<_>>();
+
+            if predecessors.is_empty() {
+                println!("\t{prefix:>5} {}", title(db, node));
+            } else {
+                println!(
+                    "\t{prefix:>5} {} <- {}",
+                    title(db, node),
+                    predecessors.join(", ")
+                );
+            }
+        }
+    }
+
+    println!("Paths:");
+    for path in paths.iter().take(args.max_paths) {
+        let path = path.into_iter().map(|id| title(db, id)).collect::<Vec<_>>();
+        println!("\t{}", path.join(" > "));
+    }
+}
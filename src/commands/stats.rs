@@ -0,0 +1,92 @@
+use clap::Args;
+
+use crate::{
+    data::stats::{DegreeBucket, GraphStats, PageDegree},
+    error::Result,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Number of pages with the highest degrees to list
+    #[arg(short = 'n', long, default_value = "10")]
+    top: usize,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+impl ArgExecutor for StatsArgs {
+    fn execute(&self) -> Result<()> {
+        stats_cmd(self)
+    }
+}
+
+fn stats_cmd(args: &StatsArgs) -> Result<()> {
+    let db = super::load_database(&args.db)?;
+    let stats = GraphStats::new(&db, args.top);
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&stats).expect("Failed to serialize stats")
+        );
+        return Ok(());
+    }
+
+    println!("Pages:               {}", stats.pages);
+    println!("Redirects:           {}", stats.redirects);
+    println!("Links:               {}", stats.links);
+    println!("Dangling links:      {}", stats.dangling_links);
+    println!("Pages without links: {}", stats.pages_without_links);
+    println!("Self-loops:          {}", stats.self_loops);
+    println!("Duplicate links:     {}", stats.duplicate_links);
+    if let Some(components) = &stats.components {
+        println!(
+            "Components:          {} (largest: {} pages)",
+            components.count, components.largest
+        );
+    }
+
+    print_histogram("Out-degree", &stats.out_degree);
+    print_histogram("In-degree", &stats.in_degree);
+    print_top("most links", &stats.top_out_degree);
+    print_top("most backlinks", &stats.top_in_degree);
+
+    println!("\nRedirect chains:");
+    println!("\t{:>8}  {:>10}", "length", "redirects");
+    for (length, count) in &stats.redirect_chains {
+        println!("\t{:>8}  {:>10}", length, count);
+    }
+    if stats.redirect_cycles > 0 {
+        println!("\t{:>8}  {:>10}", "cycle", stats.redirect_cycles);
+    }
+
+    Ok(())
+}
+
+fn print_histogram(name: &str, buckets: &[DegreeBucket]) {
+    println!("\n{name} distribution:");
+    println!("\t{:>17}  {:>10}", "degree", "pages");
+    for bucket in buckets {
+        let range = if bucket.min == bucket.max {
+            bucket.min.to_string()
+        } else {
+            format!("{}-{}", bucket.min, bucket.max)
+        };
+        println!("\t{:>17}  {:>10}", range, bucket.pages);
+    }
+}
+
+fn print_top(name: &str, pages: &[PageDegree]) {
+    println!("\nPages with the {name}:");
+    for (i, page) in pages.iter().enumerate() {
+        println!("\t{:>4}. {:>8} {}", i + 1, page.degree, page.title);
+    }
+}
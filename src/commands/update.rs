@@ -0,0 +1,200 @@
+use clap::Args;
+use log::info;
+
+use crate::{
+    data::{
+        database::{Format, Source},
+        maps::{component_map::ComponentMap, landmark_map::LandmarkMap},
+        parsers::{links, pages, redirects},
+    },
+    error::Result,
+    indication::ProgressBuilder,
+};
+
+use super::{derive_db::detect_source, ArgExecutor};
+
+#[derive(Args, Debug)]
+pub struct UpdateArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Path to the page.sql file of the newer dump
+    #[arg(short, long)]
+    page_sql: String,
+
+    /// Path to the redirects.sql file of the newer dump
+    #[arg(short, long)]
+    redirect_sql: String,
+
+    /// Path to the pagelinks.sql file of the newer dump
+    #[arg(short = 'l', long)]
+    pagelinks_sql: String,
+
+    /// Output Path, the database is replaced if not given
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Number of threads to use
+    #[arg(short, long, default_value = "2")]
+    threads: i32,
+
+    /// Name of the wiki stored in the database, detected from the dump file names like derive-db
+    #[arg(long)]
+    wiki: Option<String>,
+
+    /// Date of the dump stored in the database, detected like --wiki
+    #[arg(long)]
+    dump_date: Option<String>,
+}
+
+impl ArgExecutor for UpdateArgs {
+    fn execute(&self) -> Result<()> {
+        update_cmd(self)
+    }
+}
+
+fn update_cmd(args: &UpdateArgs) -> Result<()> {
+    let mut db = super::load_database(&args.db)?;
+
+    if db.format == Format::Mapped {
+        return Err("Mapped databases are read-only, derive it again".into());
+    }
+
+    let had_backlinks = db.links.has_backlinks();
+    // landmarks are chosen again the same way as when the database was derived
+    let (landmarks, selection) = db
+        .landmarks
+        .as_ref()
+        .map_or((0, Default::default()), |landmarks| {
+            (landmarks.landmarks().len(), landmarks.selection())
+        });
+    let components = db.components.is_some();
+    // landmarks need backlinks to compute the distances to the landmarks
+    let build_backlinks = landmarks > 0 && !had_backlinks;
+
+    let steps = 6 + build_backlinks as u8 + (landmarks > 0) as u8 + components as u8;
+    let mut step = 5;
+    let mut next_step = || {
+        step += 1;
+        step
+    };
+
+    let pages = pages::read_and_parse_pages(
+        args.page_sql.clone(),
+        args.threads,
+        ProgressBuilder::new()
+            .with_steps(1, steps)
+            .with_message("Loading pages...")
+            .with_finish_message("Pages loaded"),
+    )?;
+    let redirects = redirects::read_and_parse_redirects(
+        args.redirect_sql.clone(),
+        args.threads,
+        ProgressBuilder::new()
+            .with_steps(2, steps)
+            .with_message("Loading redirects...")
+            .with_finish_message("Redirects loaded"),
+    )?;
+
+    let page_update = db.pages.update(
+        pages,
+        redirects,
+        ProgressBuilder::new()
+            .with_steps(3, steps)
+            .with_message("Updating pages...")
+            .with_finish_message("Pages updated"),
+    );
+    info!("{page_update:?}");
+
+    let links = links::read_and_parse_links(
+        &args.pagelinks_sql,
+        args.threads,
+        &db.pages,
+        ProgressBuilder::new()
+            .with_steps(4, steps)
+            .with_message("Loading links...")
+            .with_finish_message("Links loaded"),
+    )?;
+
+    let link_update = db.links.update(
+        links,
+        ProgressBuilder::new()
+            .with_steps(5, steps)
+            .with_message("Updating links...")
+            .with_finish_message("Links updated"),
+    );
+    info!("{link_update:?}");
+
+    // distances and components depend on all links, they are computed again like by derive-db
+    if build_backlinks {
+        db.links.build_backlinks(
+            ProgressBuilder::new()
+                .with_steps(next_step(), steps)
+                .with_message("Building backlinks...")
+                .with_finish_message("Backlinks built"),
+        );
+    }
+
+    if landmarks > 0 {
+        db.landmarks = Some(LandmarkMap::new_with_progress(
+            &db.links,
+            landmarks,
+            selection,
+            ProgressBuilder::new()
+                .with_steps(next_step(), steps)
+                .with_message("Computing landmark distances...")
+                .with_finish_message("Landmark distances computed"),
+        ));
+    }
+
+    if build_backlinks {
+        db.links.remove_backlinks();
+    }
+
+    if components {
+        db.components = Some(ComponentMap::new_with_progress(
+            &db.links,
+            ProgressBuilder::new()
+                .with_steps(next_step(), steps)
+                .with_message("Computing components...")
+                .with_finish_message("Components computed"),
+        ));
+    }
+
+    let ranks = db.ranks.take().is_some();
+
+    let detected = detect_source(&args.page_sql);
+    db.source = Source {
+        wiki: args.wiki.clone().or(detected.wiki),
+        dump_date: args.dump_date.clone().or(detected.dump_date),
+    };
+
+    {
+        let spinner = ProgressBuilder::spinner()
+            .with_message("Serializing and writing file")
+            .with_steps(next_step(), steps)
+            .with_finish_message("Serialized and written to file")
+            .build();
+        spinner.enable_background();
+
+        db.to_file(args.output.as_deref().unwrap_or(&args.db), true)?;
+
+        spinner.finish();
+    }
+
+    println!(
+        "Pages:     +{} added, -{} removed, {} renamed",
+        page_update.added, page_update.removed, page_update.renamed
+    );
+    println!("Redirects: {} changed", page_update.redirects_changed);
+    println!(
+        "Links:     +{} added, -{} removed on {} pages",
+        link_update.added, link_update.removed, link_update.changed_pages
+    );
+    if ranks {
+        println!("PageRank scores were removed, store them again with rank -s");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use clap::Args;
+
+use crate::{
+    data::{database::Database, verify},
+    error::{Error, Result},
+    indication::ProgressBuilder,
+};
+
+use super::ArgExecutor;
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Database derived using derive-db command
+    #[arg(short, long)]
+    db: String,
+
+    /// Number of violations to print per check
+    #[arg(short = 'n', long, default_value = "5")]
+    examples: usize,
+}
+
+impl ArgExecutor for VerifyArgs {
+    fn execute(&self) -> Result<()> {
+        verify_cmd(self)
+    }
+}
+
+fn verify_cmd(args: &VerifyArgs) -> Result<()> {
+    let spinner = ProgressBuilder::spinner()
+        .with_message("🔍 Computing checksum")
+        .build();
+    spinner.enable_background();
+    let checksum = Database::verify_checksum(&args.db);
+    spinner.finish();
+    let checksum = checksum?;
+
+    if !checksum.is_valid() {
+        println!(
+            "❌ Checksum {:08x} does not match the stored checksum {:08x}",
+            checksum.computed, checksum.stored
+        );
+        // the content can't be trusted, so the links and pages are not loaded
+        return Err(Error::Corrupt("checksum mismatch".to_string()));
+    }
+    println!("✅ Checksum {:08x}", checksum.computed);
+
+    let db = super::load_database(&args.db)?;
+
+    let spinner = ProgressBuilder::spinner()
+        .with_message("🔍 Checking links and pages")
+        .build();
+    spinner.enable_background();
+    let checks = verify::check(&db, args.examples);
+    spinner.finish();
+
+    let mut failed = 0;
+
+    for check in &checks {
+        if check.passed() {
+            println!("✅ {}", check.name);
+            continue;
+        }
+
+        failed += 1;
+        println!("❌ {} ({} violations)", check.name, check.violations);
+        for example in &check.examples {
+            println!("\t{example}");
+        }
+        if check.violations > check.examples.len() {
+            println!("\t... and {} more", check.violations - check.examples.len());
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Corrupt(format!("{failed} checks failed")));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use log::debug;
+
+use crate::data::maps::link_map::LinkMap;
+
+/// All shortest paths between two pages, stored as a DAG of predecessors
+#[derive(Debug)]
+pub struct ShortestPaths {
+    start: i32,
+    end: i32,
+    // node -> distance from start, only nodes on a shortest path
+    distance: HashMap<i32, usize>,
+    // node -> predecessors on a shortest path
+    predecessors: HashMap<i32, Vec<i32>>,
+}
+
+impl ShortestPaths {
+    /// Number of links on each shortest path
+    pub fn length(&self) -> usize {
+        self.distance[&self.end]
+    }
+
+    /// Number of distinct shortest paths, saturating at `u64::MAX`
+    pub fn count(&self) -> u64 {
+        let mut counts: HashMap<i32, u64> = HashMap::from([(self.start, 1)]);
+
+        for level in self.levels().into_iter().skip(1) {
+            for node in level {
+                let count = self
+                    .predecessors(node)
+                    .iter()
+                    .fold(0u64, |sum, pred| sum.saturating_add(counts[pred]));
+                counts.insert(node, count);
+            }
+        }
+
+        counts[&self.end]
+    }
+
+    /// Nodes of the DAG grouped by their distance from the start
+    pub fn levels(&self) -> Vec<Vec<i32>> {
+        let mut levels = vec![vec![]; self.length() + 1];
+        for (&node, &distance) in &self.distance {
+            levels[distance].push(node);
+        }
+        for level in levels.iter_mut() {
+            level.sort_unstable();
+        }
+
+        levels
+    }
+
+    pub fn predecessors(&self, node: i32) -> &[i32] {
+        self.predecessors.get(&node).map_or(&[], |p| p.as_slice())
+    }
+
+    /// Lazily enumerates the shortest paths, use `take` to cap the number of paths
+    pub fn iter(&self) -> PathIter<'_> {
+        PathIter {
+            paths: self,
+            stack: vec![(self.end, 0)],
+        }
+    }
+}
+
+pub struct PathIter<'a> {
+    paths: &'a ShortestPaths,
+    // (node, index of the next predecessor to visit), walking from the end to the start
+    stack: Vec<(i32, usize)>,
+}
+
+impl Iterator for PathIter<'_> {
+    type Item = Vec<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(node, index)) = self.stack.last() {
+            if node == self.paths.start {
+                let path = self.stack.iter().rev().map(|&(node, _)| node).collect();
+                self.stack.pop();
+                return Some(path);
+            }
+
+            match self.paths.predecessors(node).get(index) {
+                Some(&pred) => {
+                    self.stack.last_mut().unwrap().1 += 1;
+                    self.stack.push((pred, 0));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Like [`super::bfs::find_shortest_path`], but keeps every predecessor on the shortest level
+pub fn find_all_shortest_paths(start: i32, end: i32, links: &LinkMap) -> Option<ShortestPaths> {
+    let mut distance = HashMap::from([(start, 0)]);
+    let mut predecessors: HashMap<i32, Vec<i32>> = HashMap::new();
+
+    let mut frontier = vec![start];
+    let mut depth = 0;
+
+    while !distance.contains_key(&end) {
+        if frontier.is_empty() {
+            return None;
+        }
+
+        let mut next = vec![];
+        for &at in &frontier {
+            for &neighbor in links.get(at).into_iter().flatten() {
+                match distance.get(&neighbor) {
+                    None => {
+                        distance.insert(neighbor, depth + 1);
+                        predecessors.insert(neighbor, vec![at]);
+                        next.push(neighbor);
+                    }
+                    Some(&d) if d == depth + 1 => {
+                        // all links of `at` are handled in a row, so a duplicate link can
+                        // only repeat the last predecessor
+                        let preds = predecessors.get_mut(&neighbor).unwrap();
+                        if preds.last() != Some(&at) {
+                            preds.push(at);
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        frontier = next;
+        depth += 1;
+    }
+
+    debug!("Found shortest paths of length {}", depth);
+
+    // keep only the nodes that lie on a shortest path to the end
+    let mut dag_distance = HashMap::from([(end, distance[&end])]);
+    let mut dag_predecessors = HashMap::new();
+    let mut stack = vec![end];
+    while let Some(node) = stack.pop() {
+        if node == start {
+            continue;
+        }
+
+        let preds = predecessors.remove(&node).unwrap();
+        for &pred in &preds {
+            if let Entry::Vacant(e) = dag_distance.entry(pred) {
+                e.insert(distance[&pred]);
+                stack.push(pred);
+            }
+        }
+        dag_predecessors.insert(node, preds);
+    }
+
+    Some(ShortestPaths {
+        start,
+        end,
+        distance: dag_distance,
+        predecessors: dag_predecessors,
+    })
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn start_is_end() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 1)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::find_all_shortest_paths(1, 1, &link_map).unwrap();
+
+        assert_eq!(paths.length(), 0);
+        assert_eq!(paths.count(), 1);
+        assert_eq!(paths.iter().collect::<Vec<_>>(), vec![vec![1]]);
+    }
+
+    #[test]
+    fn no_way() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        assert!(super::find_all_shortest_paths(2, 1, &link_map).is_none());
+    }
+
+    #[test]
+    fn only_shortest_paths() {
+        // 1->2->4, 1->3->4 and the longer 1->5->6->4
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (1, 5), (2, 4), (3, 4), (5, 6), (6, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::find_all_shortest_paths(1, 4, &link_map).unwrap();
+
+        assert_eq!(paths.length(), 2);
+        assert_eq!(paths.count(), 2);
+        assert_eq!(paths.levels(), vec![vec![1], vec![2, 3], vec![4]]);
+
+        let mut all: Vec<_> = paths.iter().collect();
+        all.sort();
+        assert_eq!(all, vec![vec![1, 2, 4], vec![1, 3, 4]]);
+    }
+
+    #[test]
+    fn counts_without_enumerating() {
+        // diamond chain: two choices at each of three steps
+        let link_map = LinkMap::new_with_progress(
+            vec![
+                (1, 2),
+                (1, 3),
+                (2, 4),
+                (3, 4),
+                (4, 5),
+                (4, 6),
+                (5, 7),
+                (6, 7),
+                (7, 8),
+                (7, 9),
+                (8, 10),
+                (9, 10),
+            ]
+            .into_iter()
+            .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::find_all_shortest_paths(1, 10, &link_map).unwrap();
+
+        assert_eq!(paths.length(), 6);
+        assert_eq!(paths.count(), 8);
+        assert_eq!(paths.iter().count(), 8);
+        assert_eq!(paths.iter().take(3).count(), 3);
+        assert!(paths.iter().all(|path| path.len() == 7));
+    }
+}
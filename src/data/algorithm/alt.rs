@@ -0,0 +1,208 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use log::debug;
+
+use crate::data::{
+    algorithm::limits::{Limit, LimitTracker, SearchLimits, SearchResult},
+    maps::{landmark_map::LandmarkMap, link_map::LinkMap},
+};
+
+fn rebuild_path(predecessors: &HashMap<i32, i32>, start: i32, end: i32) -> Vec<i32> {
+    let mut path = vec![end];
+    let mut at = end;
+    while at != start {
+        at = predecessors[&at];
+        path.push(at);
+    }
+    path.reverse();
+
+    path
+}
+
+/// A* search using landmark lower bounds (ALT). Pages the landmarks prove to be unable to reach
+/// `end` are never expanded. The bounds are consistent, so the returned path is a shortest path.
+pub fn search(
+    start: i32,
+    end: i32,
+    links: &LinkMap,
+    landmarks: &LandmarkMap,
+    limits: &SearchLimits,
+) -> SearchResult {
+    let mut tracker = LimitTracker::start(limits);
+    let mut depth_limited = false;
+
+    let mut distance = HashMap::from([(start, 0u32)]);
+    let mut predecessor = HashMap::new();
+    // ordered by estimated total length, then prefer pages farther from the start
+    let mut queue = BinaryHeap::new();
+
+    match landmarks.lower_bound(start, end) {
+        Some(bound) => queue.push(Reverse((bound, Reverse(0), start))),
+        None => return SearchResult::NoPath,
+    }
+
+    let mut steps: u64 = 0;
+
+    while let Some(Reverse((_, Reverse(at_distance), at))) = queue.pop() {
+        if at == end {
+            debug!("Found path in {} steps", steps);
+            return SearchResult::Found(rebuild_path(&predecessor, start, end));
+        }
+
+        // outdated queue entry, the page was reached on a shorter way already
+        if at_distance > distance[&at] {
+            continue;
+        }
+
+        if let Some(stop) = tracker.expand(1) {
+            return stop;
+        }
+
+        for &neighbor in links.get(at).into_iter().flatten() {
+            let neighbor_distance = at_distance + 1;
+            if distance
+                .get(&neighbor)
+                .is_some_and(|&known| known <= neighbor_distance)
+            {
+                continue;
+            }
+
+            let Some(bound) = landmarks.lower_bound(neighbor, end) else {
+                continue;
+            };
+
+            // even the shortest possible path over this page would be too long
+            if !tracker.depth_allowed(neighbor_distance + bound - 1) {
+                depth_limited = true;
+                continue;
+            }
+
+            distance.insert(neighbor, neighbor_distance);
+            predecessor.insert(neighbor, at);
+            queue.push(Reverse((
+                neighbor_distance + bound,
+                Reverse(neighbor_distance),
+                neighbor,
+            )));
+        }
+
+        steps += 1;
+    }
+
+    if depth_limited {
+        SearchResult::LimitReached(Limit::Depth)
+    } else {
+        SearchResult::NoPath
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::maps::{
+            landmark_map::{LandmarkMap, LandmarkSelection},
+            link_map::LinkMap,
+        },
+        indication::ProgressBuilder,
+    };
+    use proptest::prelude::*;
+
+    fn maps(links: Vec<(i32, i32)>, landmarks: usize) -> (LinkMap, LandmarkMap) {
+        let mut link_map =
+            LinkMap::new_with_progress(links.into_iter().collect(), ProgressBuilder::empty());
+        link_map.build_backlinks(ProgressBuilder::empty());
+
+        let landmark_map = LandmarkMap::new_with_progress(
+            &link_map,
+            landmarks,
+            LandmarkSelection::Farthest,
+            ProgressBuilder::empty(),
+        );
+
+        (link_map, landmark_map)
+    }
+
+    #[test]
+    fn start_is_end() {
+        let (links, landmarks) = maps(vec![(1, 2), (1, 3), (3, 2)], 1);
+
+        assert_eq!(
+            super::search(1, 1, &links, &landmarks, &Default::default()).path(),
+            Some(vec![1])
+        );
+    }
+
+    #[test]
+    fn no_way() {
+        let (links, landmarks) = maps(vec![(1, 2), (1, 3), (3, 2)], 2);
+
+        assert_eq!(
+            super::search(2, 1, &links, &landmarks, &Default::default()).path(),
+            None
+        );
+    }
+
+    #[test]
+    fn multiple_possibilities_one_shortest() {
+        // path over 1->2->3->4 and 1->5->4
+        let (links, landmarks) = maps(vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)], 2);
+
+        assert_eq!(
+            super::search(1, 4, &links, &landmarks, &Default::default()).path(),
+            Some(vec![1, 5, 4])
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        use crate::data::algorithm::limits::{Limit, SearchLimits, SearchResult};
+
+        let (links, landmarks) = maps(vec![(1, 2), (2, 3), (3, 4)], 2);
+
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &links, &landmarks, &limits),
+            SearchResult::Found(vec![1, 2, 3, 4])
+        );
+
+        let limits = SearchLimits {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &links, &landmarks, &limits),
+            SearchResult::LimitReached(Limit::Depth)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn same_length_as_bfs(
+            links in prop::collection::vec((0..30, 0..30), 0..120),
+            landmarks in 0..4usize,
+            start in 0..30,
+            end in 0..30,
+        ) {
+            let (link_map, landmark_map) = maps(links.clone(), landmarks);
+
+            let expected = crate::data::algorithm::bfs::find_shortest_path(start, end, &link_map);
+            let path = super::search(start, end, &link_map, &landmark_map, &Default::default()).path();
+
+            prop_assert_eq!(path.as_ref().map(Vec::len), expected.map(|p| p.len()));
+
+            if let Some(path) = path {
+                prop_assert_eq!(path.first(), Some(&start));
+                prop_assert_eq!(path.last(), Some(&end));
+                for edge in path.windows(2) {
+                    prop_assert!(links.contains(&(edge[0], edge[1])));
+                }
+            }
+        }
+    }
+}
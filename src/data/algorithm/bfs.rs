@@ -1,8 +1,11 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
 use log::debug;
 
-use crate::data::maps::link_map::LinkMap;
+use crate::data::{
+    algorithm::limits::{Limit, LimitTracker, SearchLimits, SearchResult},
+    maps::link_map::LinkMap,
+};
 
 fn rebuild_path(predecessors: HashMap<i32, i32>, start: i32, end: i32) -> Vec<i32> {
     let mut out_path = VecDeque::new();
@@ -20,46 +23,110 @@ fn rebuild_path(predecessors: HashMap<i32, i32>, start: i32, end: i32) -> Vec<i3
 }
 
 pub fn find_shortest_path(start: i32, end: i32, links: &LinkMap) -> Option<Vec<i32>> {
+    find_shortest_path_where(start, end, links, |_, _| true)
+}
+
+/// Like [`find_shortest_path`], but only follows links `(from, to)` for which `allowed` returns true
+pub fn find_shortest_path_where(
+    start: i32,
+    end: i32,
+    links: &LinkMap,
+    allowed: impl Fn(i32, i32) -> bool,
+) -> Option<Vec<i32>> {
+    search_where(start, end, links, allowed, &SearchLimits::default()).path()
+}
+
+/// Like [`find_shortest_path`], but stops at the given limits
+pub fn search(start: i32, end: i32, links: &LinkMap, limits: &SearchLimits) -> SearchResult {
+    search_where(start, end, links, |_, _| true, limits)
+}
+
+/// Like [`find_shortest_path_where`], but stops at the given limits
+pub fn search_where(
+    start: i32,
+    end: i32,
+    links: &LinkMap,
+    allowed: impl Fn(i32, i32) -> bool,
+    limits: &SearchLimits,
+) -> SearchResult {
     if start == end {
-        return Some(vec![start]);
+        return SearchResult::Found(vec![start]);
     }
 
+    let mut tracker = LimitTracker::start(limits);
+    let mut depth_limited = false;
+
     let mut queue = VecDeque::new();
     let mut predecessor = HashMap::new();
     let mut visited = HashSet::new(); // note: having a set of visited nodes improves performance by a few percent while increasing memory usage
 
-    queue.push_back(start);
+    queue.push_back((start, 0));
     predecessor.insert(start, start);
     visited.insert(start);
 
     let mut steps: u64 = 0;
 
-    while let Some(at) = queue.pop_front() {
+    while let Some((at, depth)) = queue.pop_front() {
         let neighbors = links.get(at);
 
         if neighbors.is_none() {
             continue;
         }
 
+        if !tracker.depth_allowed(depth) {
+            depth_limited = true;
+            continue;
+        }
+
+        if let Some(stop) = tracker.expand(1) {
+            return stop;
+        }
+
         for &neighbor in neighbors.unwrap() {
-            if visited.contains(&neighbor) {
+            if visited.contains(&neighbor) || !allowed(at, neighbor) {
                 continue;
             }
 
-            queue.push_back(neighbor);
+            queue.push_back((neighbor, depth + 1));
             predecessor.insert(neighbor, at);
             visited.insert(neighbor);
 
             if neighbor == end {
                 debug!("Found path in {} steps", steps);
-                return Some(rebuild_path(predecessor, start, end));
+                return SearchResult::Found(rebuild_path(predecessor, start, end));
             }
         }
 
         steps += 1;
     }
 
-    None
+    if depth_limited {
+        SearchResult::LimitReached(Limit::Depth)
+    } else {
+        SearchResult::NoPath
+    }
+}
+
+/// Distances from `start` to every page reachable by following `neighbors`
+pub fn distances<'a>(
+    start: i32,
+    neighbors: impl Fn(i32) -> Option<&'a [i32]>,
+) -> HashMap<i32, u32> {
+    let mut distances = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(at) = queue.pop_front() {
+        let distance = distances[&at] + 1;
+
+        for &neighbor in neighbors(at).into_iter().flatten() {
+            if let Entry::Vacant(e) = distances.entry(neighbor) {
+                e.insert(distance);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    distances
 }
 
 mod test {
@@ -145,4 +212,95 @@ mod test {
 
         assert_eq!(path, Some(vec![1, 2, 3, 4]));
     }
+
+    #[test]
+    fn filtered_links_are_skipped() {
+        // path over 1->2->3->4 and 1->5->4
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let path = super::find_shortest_path_where(1, 4, &link_map, |_, to| to != 5);
+        assert_eq!(path, Some(vec![1, 2, 3, 4]));
+
+        let path = super::find_shortest_path_where(1, 4, &link_map, |from, _| from != 1);
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn distances() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2), (2, 4), (5, 1)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let distances = super::distances(1, |id| link_map.get(id));
+
+        assert_eq!(
+            distances,
+            [(1, 0), (2, 1), (3, 1), (4, 2)].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn limits() {
+        use crate::data::algorithm::limits::{
+            CancellationToken, Limit, SearchLimits, SearchResult,
+        };
+
+        // path over 1->2->3->4 and 1->5->4
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let limits = SearchLimits {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &link_map, &limits),
+            SearchResult::Found(vec![1, 5, 4])
+        );
+        assert_eq!(
+            super::search(2, 5, &link_map, &limits),
+            SearchResult::NoPath
+        );
+
+        let limits = SearchLimits {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &link_map, &limits),
+            SearchResult::LimitReached(Limit::Depth)
+        );
+
+        let limits = SearchLimits {
+            max_expanded: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &link_map, &limits),
+            SearchResult::LimitReached(Limit::Expanded)
+        );
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let limits = SearchLimits {
+            cancel: Some(cancel),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &link_map, &limits),
+            SearchResult::Cancelled
+        );
+    }
 }
@@ -0,0 +1,294 @@
+use std::collections::{HashMap, VecDeque};
+
+use log::debug;
+
+use crate::data::{
+    algorithm::{
+        bfs,
+        limits::{Limit, LimitTracker, SearchLimits, SearchResult},
+    },
+    maps::link_map::LinkMap,
+};
+
+// node -> (next node towards the search origin, distance to the search origin)
+type Visited = HashMap<i32, (i32, u32)>;
+
+/// Expands one full level of a search direction and returns the best meeting edge `(from, to)` if
+/// this level touched nodes already visited by the other direction
+fn expand_level<'a>(
+    frontier: &mut Vec<i32>,
+    visited: &mut Visited,
+    other: &Visited,
+    neighbors: impl Fn(i32) -> Option<&'a [i32]>,
+    tracker: &mut LimitTracker,
+) -> Result<Option<(i32, i32)>, SearchResult> {
+    let mut next = vec![];
+    let mut best: Option<(u32, (i32, i32))> = None;
+
+    for &at in frontier.iter() {
+        let depth = visited[&at].1;
+
+        if let Some(stop) = tracker.expand(1) {
+            return Err(stop);
+        }
+
+        for &neighbor in neighbors(at).into_iter().flatten() {
+            if let Some(&(_, other_depth)) = other.get(&neighbor) {
+                let length = depth + 1 + other_depth;
+                if best.is_none_or(|(best_length, _)| length < best_length) {
+                    best = Some((length, (at, neighbor)));
+                }
+            }
+
+            if visited.contains_key(&neighbor) {
+                continue;
+            }
+
+            visited.insert(neighbor, (at, depth + 1));
+            next.push(neighbor);
+        }
+    }
+
+    *frontier = next;
+
+    Ok(best.map(|(_, edge)| edge))
+}
+
+/// Follows the stored predecessors from `from` back to the origin of the search
+fn walk_to_origin(visited: &Visited, from: i32) -> VecDeque<i32> {
+    let mut path = VecDeque::new();
+    let mut at = from;
+    path.push_back(at);
+    while let Some(&(next, _)) = visited.get(&at) {
+        if next == at {
+            break;
+        }
+        path.push_back(next);
+        at = next;
+    }
+
+    path
+}
+
+/// Searches from both ends at once, always expanding the smaller frontier. Forward levels follow
+/// the links, backward levels follow the backlinks. Falls back to [`bfs::search`] if
+/// the link map has no backlinks.
+pub fn search(start: i32, end: i32, links: &LinkMap, limits: &SearchLimits) -> SearchResult {
+    if !links.has_backlinks() {
+        debug!("No backlinks available, falling back to forward search");
+        return bfs::search(start, end, links, limits);
+    }
+
+    if start == end {
+        return SearchResult::Found(vec![start]);
+    }
+
+    search_levels(start, end, links, limits)
+}
+
+/// The level-synchronous search behind [`search`], needs backlinks. Paths have at least one link,
+/// so searching from a page to itself finds the shortest cycle through it.
+pub fn search_levels(start: i32, end: i32, links: &LinkMap, limits: &SearchLimits) -> SearchResult {
+    let mut tracker = LimitTracker::start(limits);
+    let (mut forward_depth, mut backward_depth) = (0, 0);
+
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![end];
+
+    let mut forward_visited = Visited::from([(start, (start, 0))]);
+    let mut backward_visited = Visited::from([(end, (end, 0))]);
+
+    let mut levels: u64 = 0;
+
+    while !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        levels += 1;
+
+        // no meeting yet, so any path is longer than both searched depths together
+        if !tracker.depth_allowed(forward_depth + backward_depth) {
+            return SearchResult::LimitReached(Limit::Depth);
+        }
+
+        // the meeting edge always points from the start side to the end side
+        let meeting = if forward_frontier.len() <= backward_frontier.len() {
+            forward_depth += 1;
+            expand_level(
+                &mut forward_frontier,
+                &mut forward_visited,
+                &backward_visited,
+                |id| links.get(id),
+                &mut tracker,
+            )
+        } else {
+            backward_depth += 1;
+            expand_level(
+                &mut backward_frontier,
+                &mut backward_visited,
+                &forward_visited,
+                |id| links.backlinks(id),
+                &mut tracker,
+            )
+            .map(|meeting| meeting.map(|(to, from)| (from, to)))
+        };
+
+        let meeting = match meeting {
+            Ok(meeting) => meeting,
+            Err(stop) => return stop,
+        };
+
+        if let Some((from, to)) = meeting {
+            debug!("Found path after expanding {} levels", levels);
+
+            let mut path = walk_to_origin(&forward_visited, from);
+            path.make_contiguous().reverse();
+            path.extend(walk_to_origin(&backward_visited, to));
+
+            return SearchResult::Found(Vec::from(path));
+        }
+    }
+
+    SearchResult::NoPath
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+    use proptest::prelude::*;
+
+    fn link_map(links: Vec<(i32, i32)>) -> LinkMap {
+        let mut map =
+            LinkMap::new_with_progress(links.into_iter().collect(), ProgressBuilder::empty());
+        map.build_backlinks(ProgressBuilder::empty());
+        map
+    }
+
+    #[test]
+    fn direct_link() {
+        let link_map = link_map(vec![(1, 2), (1, 3), (3, 2)]);
+
+        assert_eq!(
+            super::search(1, 2, &link_map, &Default::default()).path(),
+            Some(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn start_is_end() {
+        let link_map = link_map(vec![(1, 2), (1, 3), (3, 2)]);
+
+        assert_eq!(
+            super::search(1, 1, &link_map, &Default::default()).path(),
+            Some(vec![1])
+        );
+    }
+
+    #[test]
+    fn no_way() {
+        let link_map = link_map(vec![(1, 2), (1, 3), (3, 2)]);
+
+        assert_eq!(
+            super::search(2, 1, &link_map, &Default::default()).path(),
+            None
+        );
+    }
+
+    #[test]
+    fn multiple_possibilities_one_shortest() {
+        // path over 1->2->3->4 and 1->5->4
+        let link_map = link_map(vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)]);
+
+        assert_eq!(
+            super::search(1, 4, &link_map, &Default::default()).path(),
+            Some(vec![1, 5, 4])
+        );
+    }
+
+    #[test]
+    fn meets_in_the_middle() {
+        // wide start side, so the search has to expand from the end
+        let link_map = link_map(vec![
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (1, 5),
+            (2, 6),
+            (6, 7),
+            (7, 8),
+            (3, 8),
+        ]);
+
+        assert_eq!(
+            super::search(1, 8, &link_map, &Default::default()).path(),
+            Some(vec![1, 3, 8])
+        );
+        assert_eq!(
+            super::search(2, 8, &link_map, &Default::default()).path(),
+            Some(vec![2, 6, 7, 8])
+        );
+    }
+
+    #[test]
+    fn falls_back_without_backlinks() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        assert_eq!(
+            super::search(1, 4, &link_map, &Default::default()).path(),
+            Some(vec![1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        use crate::data::algorithm::limits::{Limit, SearchLimits, SearchResult};
+
+        let link_map = link_map(vec![(1, 2), (2, 3), (3, 4)]);
+
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &link_map, &limits),
+            SearchResult::Found(vec![1, 2, 3, 4])
+        );
+        assert_eq!(
+            super::search(4, 1, &link_map, &limits),
+            SearchResult::NoPath
+        );
+
+        let limits = SearchLimits {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::search(1, 4, &link_map, &limits),
+            SearchResult::LimitReached(Limit::Depth)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn same_length_as_bfs(
+            links in prop::collection::vec((0..30, 0..30), 0..120),
+            start in 0..30,
+            end in 0..30,
+        ) {
+            let link_map = link_map(links.clone());
+
+            let expected = crate::data::algorithm::bfs::find_shortest_path(start, end, &link_map);
+            let path = super::search(start, end, &link_map, &Default::default()).path();
+
+            prop_assert_eq!(path.as_ref().map(Vec::len), expected.map(|p| p.len()));
+
+            if let Some(path) = path {
+                prop_assert_eq!(path.first(), Some(&start));
+                prop_assert_eq!(path.last(), Some(&end));
+                for edge in path.windows(2) {
+                    prop_assert!(links.contains(&(edge[0], edge[1])));
+                }
+            }
+        }
+    }
+}
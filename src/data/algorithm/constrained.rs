@@ -0,0 +1,195 @@
+use std::{collections::HashSet, time::Instant};
+
+use crate::data::{
+    algorithm::{
+        bfs,
+        limits::{Limit, SearchLimits, SearchResult},
+    },
+    maps::link_map::LinkMap,
+};
+
+/// Restrictions for a path search
+#[derive(Debug, Default, Clone)]
+pub struct Constraints {
+    /// Pages the path must not pass through (start, end and waypoints are always allowed)
+    pub avoid: HashSet<i32>,
+    /// Pages the path has to pass through, in this order
+    pub via: Vec<i32>,
+}
+
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self.avoid.is_empty() && self.via.is_empty()
+    }
+}
+
+/// Finds the shortest path from `start` to `end` that satisfies `constraints`. With waypoints the
+/// path is the concatenation of the shortest paths between consecutive waypoints, so a page may
+/// appear in more than one segment. The depth and the timeout apply to the whole path, the
+/// expanded pages are counted per segment.
+pub fn search(
+    start: i32,
+    end: i32,
+    constraints: &Constraints,
+    links: &LinkMap,
+    limits: &SearchLimits,
+) -> SearchResult {
+    let started = Instant::now();
+
+    let waypoints = std::iter::once(start)
+        .chain(constraints.via.iter().copied())
+        .chain(std::iter::once(end))
+        .collect::<Vec<_>>();
+
+    let mut path = vec![start];
+
+    for segment in waypoints.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+
+        let used = (path.len() - 1) as u32;
+        let segment_limits = SearchLimits {
+            max_depth: match limits.max_depth {
+                Some(max) if max < used => return SearchResult::LimitReached(Limit::Depth),
+                max => max.map(|max| max - used),
+            },
+            timeout: limits
+                .timeout
+                .map(|timeout| timeout.saturating_sub(started.elapsed())),
+            ..limits.clone()
+        };
+
+        let part = bfs::search_where(
+            from,
+            to,
+            links,
+            |_, next| !constraints.avoid.contains(&next) || waypoints.contains(&next),
+            &segment_limits,
+        );
+
+        match part {
+            SearchResult::Found(part) => path.extend(part.into_iter().skip(1)),
+            stop => return stop,
+        }
+    }
+
+    SearchResult::Found(path)
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::Constraints;
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn no_constraints() {
+        // path over 1->2->3->4 and 1->5->4
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let path = super::search(
+            1,
+            4,
+            &Constraints::default(),
+            &link_map,
+            &Default::default(),
+        )
+        .path();
+
+        assert_eq!(path, Some(vec![1, 5, 4]));
+    }
+
+    #[test]
+    fn avoid_page() {
+        // path over 1->2->3->4 and 1->5->4
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let constraints = Constraints {
+            avoid: [5].into(),
+            via: vec![],
+        };
+        let path = super::search(1, 4, &constraints, &link_map, &Default::default()).path();
+        assert_eq!(path, Some(vec![1, 2, 3, 4]));
+
+        let constraints = Constraints {
+            avoid: [3, 5].into(),
+            via: vec![],
+        };
+        let path = super::search(1, 4, &constraints, &link_map, &Default::default()).path();
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn avoided_end_is_reachable() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let constraints = Constraints {
+            avoid: [3].into(),
+            via: vec![],
+        };
+        let path = super::search(1, 3, &constraints, &link_map, &Default::default()).path();
+
+        assert_eq!(path, Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn avoided_waypoints_are_allowed_on_all_segments() {
+        // 1 only reaches 2 over 3
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 3), (3, 2), (2, 3)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let constraints = Constraints {
+            avoid: [3].into(),
+            via: vec![2],
+        };
+        let path = super::search(1, 3, &constraints, &link_map, &Default::default()).path();
+
+        assert_eq!(path, Some(vec![1, 3, 2, 3]));
+    }
+
+    #[test]
+    fn via_pages() {
+        // path over 1->2->3->4 and 1->5->4, 3 also links to 5
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4), (3, 5)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let constraints = Constraints {
+            avoid: Default::default(),
+            via: vec![3],
+        };
+        let path = super::search(1, 4, &constraints, &link_map, &Default::default()).path();
+        assert_eq!(path, Some(vec![1, 2, 3, 4]));
+
+        let constraints = Constraints {
+            avoid: Default::default(),
+            via: vec![2, 5],
+        };
+        let path = super::search(1, 4, &constraints, &link_map, &Default::default()).path();
+        assert_eq!(path, Some(vec![1, 2, 3, 5, 4]));
+
+        let constraints = Constraints {
+            avoid: Default::default(),
+            via: vec![5, 2],
+        };
+        let path = super::search(1, 4, &constraints, &link_map, &Default::default()).path();
+        assert_eq!(path, None);
+    }
+}
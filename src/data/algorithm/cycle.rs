@@ -0,0 +1,148 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crate::data::{
+    algorithm::{
+        bidirectional,
+        limits::{Limit, LimitTracker, SearchLimits, SearchResult},
+    },
+    maps::link_map::LinkMap,
+};
+
+/// Finds the shortest cycle through `page`, the returned path starts and ends with `page`.
+/// Searches from both ends if the link map has backlinks, otherwise follows the links only.
+pub fn shortest_cycle(page: i32, links: &LinkMap, limits: &SearchLimits) -> SearchResult {
+    if links.has_backlinks() {
+        if links.backlinks(page).is_none() {
+            return SearchResult::NoPath;
+        }
+        return bidirectional::search_levels(page, page, links, limits);
+    }
+
+    let mut tracker = LimitTracker::start(limits);
+    let mut depth_limited = false;
+
+    let mut predecessor = HashMap::from([(page, page)]);
+    let mut queue = VecDeque::from([(page, 0)]);
+
+    while let Some((at, depth)) = queue.pop_front() {
+        let Some(neighbors) = links.get(at) else {
+            continue;
+        };
+
+        if !tracker.depth_allowed(depth) {
+            depth_limited = true;
+            continue;
+        }
+
+        if let Some(stop) = tracker.expand(1) {
+            return stop;
+        }
+
+        for &neighbor in neighbors {
+            if neighbor == page {
+                let mut path = vec![page];
+                let mut node = at;
+                while node != page {
+                    path.push(node);
+                    node = predecessor[&node];
+                }
+                path.push(page);
+                path.reverse();
+
+                return SearchResult::Found(path);
+            }
+
+            if let Entry::Vacant(e) = predecessor.entry(neighbor) {
+                e.insert(at);
+                queue.push_back((neighbor, depth + 1));
+            }
+        }
+    }
+
+    if depth_limited {
+        SearchResult::LimitReached(Limit::Depth)
+    } else {
+        SearchResult::NoPath
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::shortest_cycle;
+    use crate::{
+        data::{
+            algorithm::limits::{Limit, SearchLimits, SearchResult},
+            maps::link_map::LinkMap,
+        },
+        indication::ProgressBuilder,
+    };
+    use proptest::prelude::*;
+
+    fn link_maps(links: Vec<(i32, i32)>) -> [LinkMap; 2] {
+        let forward =
+            LinkMap::new_with_progress(links.iter().copied().collect(), ProgressBuilder::empty());
+        let mut both =
+            LinkMap::new_with_progress(links.into_iter().collect(), ProgressBuilder::empty());
+        both.build_backlinks(ProgressBuilder::empty());
+        [forward, both]
+    }
+
+    #[test]
+    fn cycles() {
+        // 1 -> 2 -> 3 -> 1, 1 -> 4 -> 5 -> 6 -> 1, 7 -> 7, 8 -> 1
+        for links in link_maps(vec![
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (1, 4),
+            (4, 5),
+            (5, 6),
+            (6, 1),
+            (7, 7),
+            (8, 1),
+        ]) {
+            let cycle = |page| shortest_cycle(page, &links, &Default::default()).path();
+
+            assert_eq!(cycle(1), Some(vec![1, 2, 3, 1]));
+            assert_eq!(cycle(5), Some(vec![5, 6, 1, 4, 5]));
+            assert_eq!(cycle(7), Some(vec![7, 7]));
+            assert_eq!(cycle(8), None);
+            assert_eq!(cycle(9), None);
+
+            let limits = SearchLimits {
+                max_depth: Some(3),
+                ..Default::default()
+            };
+            assert_eq!(
+                shortest_cycle(4, &links, &limits),
+                SearchResult::LimitReached(Limit::Depth)
+            );
+            assert_eq!(
+                shortest_cycle(2, &links, &limits).path(),
+                Some(vec![2, 3, 1, 2])
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn directions_agree(
+            edges in prop::collection::vec((0..15i32, 0..15i32), 0..50),
+            page in 0..15i32,
+        ) {
+            let [forward, both] = link_maps(edges.clone());
+
+            let forward = shortest_cycle(page, &forward, &Default::default()).path();
+            let both = shortest_cycle(page, &both, &Default::default()).path();
+
+            prop_assert_eq!(forward.as_ref().map(Vec::len), both.as_ref().map(Vec::len));
+            if let Some(path) = both {
+                prop_assert_eq!(path[0], page);
+                prop_assert_eq!(path[path.len() - 1], page);
+                for pair in path.windows(2) {
+                    prop_assert!(edges.contains(&(pair[0], pair[1])));
+                }
+            }
+        }
+    }
+}
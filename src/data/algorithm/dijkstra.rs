@@ -0,0 +1,271 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+};
+
+use log::debug;
+
+use crate::data::{
+    algorithm::limits::{Limit, LimitTracker, SearchLimits, SearchResult},
+    maps::{link_map::LinkMap, rank_map::RankMap},
+};
+
+/// Cost of following a link, the search finds the path with the lowest total cost. The dumps
+/// carry no position of a link in the article, so there is no weight by position.
+pub enum EdgeWeight<'a> {
+    /// Every link costs 1, like in a BFS
+    Uniform,
+    /// 1 / number of pages linking to the target, prefers well-linked pages (needs backlinks)
+    InverseInDegree(&'a LinkMap),
+    /// -ln(score of the target), minimizing the sum maximizes the product of the scores
+    PageRank(&'a RankMap),
+}
+
+impl EdgeWeight<'_> {
+    /// Cost of a link to `to`
+    pub fn cost(&self, to: i32) -> f64 {
+        match self {
+            EdgeWeight::Uniform => 1.0,
+            EdgeWeight::InverseInDegree(links) => {
+                1.0 / links.in_degree(to).unwrap_or(1).max(1) as f64
+            }
+            EdgeWeight::PageRank(ranks) => {
+                -ranks.get(to).unwrap_or(0.0).max(f64::MIN_POSITIVE).ln()
+            }
+        }
+    }
+}
+
+/// Total cost of a path
+pub fn path_cost(path: &[i32], cost: impl Fn(i32, i32) -> f64) -> f64 {
+    path.windows(2).map(|pair| cost(pair[0], pair[1])).sum()
+}
+
+#[derive(Debug, PartialEq)]
+struct State {
+    cost: f64,
+    hops: u32,
+    node: i32,
+}
+
+impl Eq for State {}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cost
+            .total_cmp(&other.cost)
+            .then(self.hops.cmp(&other.hops))
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn rebuild_path(predecessors: &HashMap<i32, i32>, start: i32, end: i32) -> Vec<i32> {
+    let mut path = vec![end];
+    let mut at = end;
+    while at != start {
+        at = predecessors[&at];
+        path.push(at);
+    }
+    path.reverse();
+
+    path
+}
+
+/// Dijkstra search for the path with the lowest total cost. `cost` gets the link source and
+/// target and must not be negative. Links with an infinite
+/// cost are never followed.
+///
+/// The depth limit is applied to the cheapest way to every page, so a path within the limit can
+/// be missed if a cheaper but longer way to one of its pages exists.
+pub fn search(
+    start: i32,
+    end: i32,
+    links: &LinkMap,
+    cost: impl Fn(i32, i32) -> f64,
+    limits: &SearchLimits,
+) -> SearchResult {
+    let mut tracker = LimitTracker::start(limits);
+    let mut depth_limited = false;
+
+    let mut best = HashMap::from([(start, 0.0)]);
+    let mut predecessor = HashMap::new();
+    let mut queue = BinaryHeap::from([Reverse(State {
+        cost: 0.0,
+        hops: 0,
+        node: start,
+    })]);
+
+    let mut steps: u64 = 0;
+
+    while let Some(Reverse(State {
+        cost: at_cost,
+        hops,
+        node: at,
+    })) = queue.pop()
+    {
+        if at == end {
+            debug!("Found path in {} steps", steps);
+            return SearchResult::Found(rebuild_path(&predecessor, start, end));
+        }
+
+        // outdated queue entry, the page was reached at a lower cost already
+        if at_cost > best[&at] {
+            continue;
+        }
+
+        let Some(neighbors) = links.get(at) else {
+            continue;
+        };
+
+        if !tracker.depth_allowed(hops) {
+            depth_limited = true;
+            continue;
+        }
+
+        if let Some(stop) = tracker.expand(1) {
+            return stop;
+        }
+
+        for &neighbor in neighbors {
+            let link_cost = cost(at, neighbor);
+            debug_assert!(link_cost >= 0.0, "negative link cost");
+            if link_cost.is_infinite() {
+                continue;
+            }
+
+            let neighbor_cost = at_cost + link_cost;
+            if best
+                .get(&neighbor)
+                .is_some_and(|&known| known <= neighbor_cost)
+            {
+                continue;
+            }
+
+            best.insert(neighbor, neighbor_cost);
+            predecessor.insert(neighbor, at);
+            queue.push(Reverse(State {
+                cost: neighbor_cost,
+                hops: hops + 1,
+                node: neighbor,
+            }));
+        }
+
+        steps += 1;
+    }
+
+    if depth_limited {
+        SearchResult::LimitReached(Limit::Depth)
+    } else {
+        SearchResult::NoPath
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{path_cost, search, EdgeWeight};
+    use crate::{
+        data::{
+            algorithm::{bfs, limits::SearchLimits},
+            maps::{link_map::LinkMap, rank_map::RankMap},
+        },
+        indication::ProgressBuilder,
+    };
+    use proptest::prelude::*;
+
+    // 1 -> 2 -> 5 and 1 -> 3 -> 4 -> 5, 6 -> {3, 4}, 7 -> 3
+    fn links() -> LinkMap {
+        let mut links = LinkMap::new_with_progress(
+            vec![
+                (1, 2),
+                (1, 3),
+                (2, 5),
+                (3, 4),
+                (4, 5),
+                (6, 3),
+                (6, 4),
+                (7, 3),
+            ]
+            .into_iter()
+            .collect(),
+            ProgressBuilder::empty(),
+        );
+        links.build_backlinks(ProgressBuilder::empty());
+        links
+    }
+
+    #[test]
+    fn uniform_is_shortest() {
+        let links = links();
+        let cost = |_, to| EdgeWeight::Uniform.cost(to);
+
+        let path = search(1, 5, &links, cost, &Default::default()).path();
+        assert_eq!(path, Some(vec![1, 2, 5]));
+        assert_eq!(path_cost(&[1, 2, 5], cost), 2.0);
+    }
+
+    #[test]
+    fn weights_change_the_path() {
+        let links = links();
+
+        // 3 has three backlinks, 4 and 5 have two, 2 has one
+        let weight = EdgeWeight::InverseInDegree(&links);
+        let path = search(1, 5, &links, |_, to| weight.cost(to), &Default::default());
+        assert_eq!(path.path(), Some(vec![1, 3, 4, 5]));
+        assert_eq!(
+            path_cost(&[1, 3, 4, 5], |_, to| weight.cost(to)),
+            1.0 / 3.0 + 0.5 + 0.5
+        );
+
+        let ranks = RankMap::new([(2, 0.01), (3, 0.5), (4, 0.5), (5, 0.1)].into());
+        let weight = EdgeWeight::PageRank(&ranks);
+        let path = search(1, 5, &links, |_, to| weight.cost(to), &Default::default());
+        assert_eq!(path.path(), Some(vec![1, 3, 4, 5]));
+    }
+
+    #[test]
+    fn infinite_costs_are_skipped() {
+        let links = links();
+        let cost = |_, to| if to == 2 { f64::INFINITY } else { 1.0 };
+
+        let path = search(1, 5, &links, cost, &Default::default()).path();
+        assert_eq!(path, Some(vec![1, 3, 4, 5]));
+        assert_eq!(search(1, 2, &links, cost, &Default::default()).path(), None);
+    }
+
+    #[test]
+    fn depth_limit() {
+        let links = links();
+        let cost = |_, _| 1.0;
+        let limits = SearchLimits {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(search(1, 4, &links, cost, &limits).path(), None);
+        assert_eq!(search(1, 3, &links, cost, &limits).path(), Some(vec![1, 3]));
+    }
+
+    proptest! {
+        #[test]
+        fn uniform_matches_bfs_length(
+            edges in prop::collection::vec((0..20i32, 0..20i32), 0..80),
+            start in 0..20i32,
+            end in 0..20i32,
+        ) {
+            let links = LinkMap::new_with_progress(edges.into_iter().collect(), ProgressBuilder::empty());
+
+            let expected = bfs::find_shortest_path(start, end, &links).map(|path| path.len());
+            let actual = search(start, end, &links, |_, _| 1.0, &Default::default())
+                .path()
+                .map(|path| path.len());
+
+            prop_assert_eq!(actual, expected);
+        }
+    }
+}
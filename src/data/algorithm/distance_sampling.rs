@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+use super::bfs;
+
+/// z-score of a two-sided 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// Sample mean with the half-width of its 95% confidence interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub margin: f64,
+}
+
+impl Estimate {
+    fn from_samples(samples: &[f64]) -> Self {
+        let n = samples.len() as f64;
+        if samples.is_empty() {
+            return Self {
+                mean: 0.0,
+                margin: 0.0,
+            };
+        }
+
+        let mean = samples.iter().sum::<f64>() / n;
+        let margin = if samples.len() > 1 {
+            let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            Z_95 * (variance / n).sqrt()
+        } else {
+            0.0
+        };
+
+        Self { mean, margin }
+    }
+}
+
+/// Farthest page found from a source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FarthestPair {
+    pub from: i32,
+    pub to: i32,
+    pub distance: u32,
+}
+
+#[derive(Debug)]
+pub struct DistanceEstimate {
+    /// Number of sources that reach at least one other page
+    pub sources: usize,
+    /// Share of the reachable pages at each distance, starting at distance 1
+    pub histogram: Vec<Estimate>,
+    /// Average length of the shortest paths to the reachable pages
+    pub average: Estimate,
+    /// Number of pages reachable from a page
+    pub reachable: Estimate,
+    /// Farthest page per source, sorted by descending distance
+    pub farthest: Vec<FarthestPair>,
+}
+
+/// Farthest page from a source, preferring the lowest id on ties
+fn farthest(from: i32, distances: &HashMap<i32, u32>) -> FarthestPair {
+    let (&to, &distance) = distances
+        .iter()
+        .max_by_key(|&(&id, &distance)| (distance, std::cmp::Reverse(id)))
+        .expect("distances contain the source");
+
+    FarthestPair { from, to, distance }
+}
+
+/// Estimates the distance distribution by running a full BFS from every source.
+///
+/// Every source contributes one sample per statistic, so the confidence intervals
+/// assume the sources were drawn uniformly at random.
+pub fn estimate(sources: &[i32], links: &LinkMap, progress: ProgressBuilder) -> DistanceEstimate {
+    let progress = progress.with_len(sources.len() as u64).build();
+
+    let mut shares: Vec<Vec<f64>> = vec![];
+    let mut averages = vec![];
+    let mut reachable = vec![];
+    let mut farthest_pairs = vec![];
+
+    for &source in sources {
+        let distances = bfs::distances(source, |id| links.get(id));
+        progress.inc(1);
+
+        let count = distances.len() - 1;
+        reachable.push(count as f64);
+        if count == 0 {
+            continue;
+        }
+
+        let mut histogram: Vec<usize> = vec![];
+        for &distance in distances.values().filter(|&&d| d > 0) {
+            let distance = distance as usize;
+            if histogram.len() < distance {
+                histogram.resize(distance, 0);
+            }
+            histogram[distance - 1] += 1;
+        }
+
+        let total: usize = histogram.iter().enumerate().map(|(i, c)| (i + 1) * c).sum();
+        averages.push(total as f64 / count as f64);
+
+        if shares.len() < histogram.len() {
+            shares.resize(histogram.len(), vec![]);
+        }
+        for (i, samples) in shares.iter_mut().enumerate() {
+            // sources that do not reach this far contribute a share of 0
+            samples.resize(averages.len() - 1, 0.0);
+            samples.push(histogram.get(i).copied().unwrap_or(0) as f64 / count as f64);
+        }
+
+        farthest_pairs.push(farthest(source, &distances));
+    }
+
+    for samples in shares.iter_mut() {
+        samples.resize(averages.len(), 0.0);
+    }
+
+    progress.finish();
+
+    farthest_pairs.sort_unstable_by_key(|pair| (std::cmp::Reverse(pair.distance), pair.from));
+
+    DistanceEstimate {
+        sources: averages.len(),
+        histogram: shares.iter().map(|s| Estimate::from_samples(s)).collect(),
+        average: Estimate::from_samples(&averages),
+        reachable: Estimate::from_samples(&reachable),
+        farthest: farthest_pairs,
+    }
+}
+
+/// Double sweep: runs a BFS from `start` and a second one from the farthest page found.
+///
+/// The farther of both sweeps is a lower bound of the diameter. In a directed graph the second
+/// sweep can reach less than the first, e.g. nothing if the farthest page has no links.
+pub fn double_sweep(start: i32, links: &LinkMap) -> FarthestPair {
+    let first = farthest(start, &bfs::distances(start, |id| links.get(id)));
+    let second = farthest(first.to, &bfs::distances(first.to, |id| links.get(id)));
+    if second.distance >= first.distance {
+        second
+    } else {
+        first
+    }
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::{double_sweep, estimate, Estimate, FarthestPair};
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn confidence_interval() {
+        let estimate = Estimate::from_samples(&[1.0, 2.0, 3.0]);
+        assert_eq!(estimate.mean, 2.0);
+        assert!((estimate.margin - 1.96 / 3f64.sqrt()).abs() < 1e-12);
+
+        assert_eq!(Estimate::from_samples(&[5.0]).margin, 0.0);
+    }
+
+    #[test]
+    fn path_graph() {
+        // 1 -> 2 -> 3 -> 4
+        let links = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let result = estimate(&[1, 3, 4], &links, ProgressBuilder::empty());
+
+        // 4 reaches no page
+        assert_eq!(result.sources, 2);
+        assert_eq!(result.reachable.mean, (3.0 + 1.0 + 0.0) / 3.0);
+        // 1: (1 + 2 + 3) / 3, 3: 1
+        assert_eq!(result.average.mean, 1.5);
+        assert_eq!(result.histogram.len(), 3);
+        assert_eq!(result.histogram[0].mean, (1.0 / 3.0 + 1.0) / 2.0);
+        assert_eq!(result.histogram[2].mean, (1.0 / 3.0) / 2.0);
+        assert_eq!(
+            result.farthest[0],
+            FarthestPair {
+                from: 1,
+                to: 4,
+                distance: 3
+            }
+        );
+
+        // the second sweeps start at 4, which has no links, so the first sweeps are kept
+        assert_eq!(double_sweep(2, &links).distance, 2);
+        assert_eq!(
+            double_sweep(1, &links),
+            FarthestPair {
+                from: 1,
+                to: 4,
+                distance: 3
+            }
+        );
+    }
+
+    #[test]
+    fn double_sweep_on_symmetric_path() {
+        // 1 <-> 2 <-> 3 <-> 4 <-> 5
+        let links = LinkMap::new_with_progress(
+            vec![
+                (1, 2),
+                (2, 1),
+                (2, 3),
+                (3, 2),
+                (3, 4),
+                (4, 3),
+                (4, 5),
+                (5, 4),
+            ]
+            .into_iter()
+            .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        assert_eq!(
+            double_sweep(3, &links),
+            FarthestPair {
+                from: 1,
+                to: 5,
+                distance: 4
+            }
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use crate::data::maps::link_map::LinkMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Follow the links of a page
+    Forward,
+    /// Follow the backlinks of a page, needs backlinks
+    Backward,
+}
+
+fn neighbors(id: i32, direction: Direction, links: &LinkMap) -> &[i32] {
+    match direction {
+        Direction::Forward => links.get(id),
+        Direction::Backward => links.backlinks(id),
+    }
+    .unwrap_or(&[])
+}
+
+/// BFS layers around `source`: layer `k` contains the pages exactly `k` links away (in the given
+/// direction), layer 0 is the source itself. Stops after `max_depth` layers if given. Without a
+/// depth limit the number of layers minus one is the eccentricity of the source.
+pub fn distance_layers(
+    source: i32,
+    direction: Direction,
+    max_depth: Option<u32>,
+    links: &LinkMap,
+) -> Vec<Vec<i32>> {
+    let mut visited = HashSet::from([source]);
+    let mut layers = vec![vec![source]];
+
+    while max_depth.is_none_or(|max| layers.len() <= max as usize) {
+        let mut next = vec![];
+        for &at in layers.last().unwrap() {
+            for &neighbor in neighbors(at, direction, links) {
+                if visited.insert(neighbor) {
+                    next.push(neighbor);
+                }
+            }
+        }
+
+        if next.is_empty() {
+            break;
+        }
+
+        layers.push(next);
+    }
+
+    layers
+}
+
+/// Whether a page of the last layer links to a page outside of all `layers`, i.e. whether the
+/// depth limit of `distance_layers` cut off more layers
+pub fn reaches_beyond(layers: &[Vec<i32>], direction: Direction, links: &LinkMap) -> bool {
+    let visited = layers.iter().flatten().collect::<HashSet<_>>();
+    layers.last().into_iter().flatten().any(|&at| {
+        neighbors(at, direction, links)
+            .iter()
+            .any(|neighbor| !visited.contains(neighbor))
+    })
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::Direction;
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn forward_layers() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2), (2, 4), (4, 1), (5, 1)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let layers = super::distance_layers(1, Direction::Forward, None, &link_map);
+        assert_eq!(layers, vec![vec![1], vec![2, 3], vec![4]]);
+
+        let layers = super::distance_layers(1, Direction::Forward, Some(1), &link_map);
+        assert_eq!(layers, vec![vec![1], vec![2, 3]]);
+        assert!(super::reaches_beyond(
+            &layers,
+            Direction::Forward,
+            &link_map
+        ));
+
+        // 4 only links back to 1
+        let layers = super::distance_layers(1, Direction::Forward, Some(2), &link_map);
+        assert!(!super::reaches_beyond(
+            &layers,
+            Direction::Forward,
+            &link_map
+        ));
+
+        let layers = super::distance_layers(1, Direction::Forward, Some(0), &link_map);
+        assert_eq!(layers, vec![vec![1]]);
+    }
+
+    #[test]
+    fn backward_layers() {
+        let mut link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2), (2, 4), (4, 1), (5, 1)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+        link_map.build_backlinks(ProgressBuilder::empty());
+
+        let layers = super::distance_layers(1, Direction::Backward, None, &link_map);
+        assert_eq!(layers, vec![vec![1], vec![4, 5], vec![2], vec![3]]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+// the clock is only read every this many expanded pages
+const CLOCK_INTERVAL: u64 = 256;
+
+/// Shared flag to abort a running search from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Bounds for a single search, all unlimited by default
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    /// Maximum number of links on the path
+    pub max_depth: Option<u32>,
+    /// Maximum number of pages whose links are expanded
+    pub max_expanded: Option<u64>,
+    pub timeout: Option<Duration>,
+    pub cancel: Option<CancellationToken>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Depth,
+    Expanded,
+    Timeout,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SearchResult {
+    Found(Vec<i32>),
+    /// Every reachable page was searched, the target is not reachable
+    NoPath,
+    /// The search stopped at a limit, a path may still exist
+    LimitReached(Limit),
+    Cancelled,
+}
+
+impl SearchResult {
+    pub fn path(self) -> Option<Vec<i32>> {
+        match self {
+            SearchResult::Found(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps track of the resources a running search used
+pub struct LimitTracker<'a> {
+    limits: &'a SearchLimits,
+    started: Instant,
+    expanded: u64,
+}
+
+impl<'a> LimitTracker<'a> {
+    pub fn start(limits: &'a SearchLimits) -> Self {
+        Self {
+            limits,
+            started: Instant::now(),
+            expanded: 0,
+        }
+    }
+
+    /// Whether a page at `depth` may still be expanded without exceeding the maximum depth
+    pub fn depth_allowed(&self, depth: u32) -> bool {
+        self.limits.max_depth.is_none_or(|max| depth < max)
+    }
+
+    /// Counts `count` expanded pages and returns the reason to stop, if any
+    pub fn expand(&mut self, count: u64) -> Option<SearchResult> {
+        let before = self.expanded;
+        self.expanded += count;
+
+        if self
+            .limits
+            .max_expanded
+            .is_some_and(|max| self.expanded > max)
+        {
+            return Some(SearchResult::LimitReached(Limit::Expanded));
+        }
+
+        if self
+            .limits
+            .cancel
+            .as_ref()
+            .is_some_and(|c| c.is_cancelled())
+        {
+            return Some(SearchResult::Cancelled);
+        }
+
+        // reading the clock is comparably expensive, so it is only done every few pages
+        if before / CLOCK_INTERVAL != self.expanded / CLOCK_INTERVAL || count > 1 {
+            return self.timed_out();
+        }
+
+        None
+    }
+
+    fn timed_out(&self) -> Option<SearchResult> {
+        if self
+            .limits
+            .timeout
+            .is_some_and(|timeout| self.started.elapsed() > timeout)
+        {
+            return Some(SearchResult::LimitReached(Limit::Timeout));
+        }
+
+        None
+    }
+}
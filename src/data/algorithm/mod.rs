@@ -1 +1,16 @@
+pub mod all_paths;
+pub mod alt;
 pub mod bfs;
+pub mod bidirectional;
+pub mod constrained;
+pub mod cycle;
+pub mod dijkstra;
+pub mod distance_sampling;
+pub mod layers;
+pub mod limits;
+pub mod pagerank;
+pub mod parallel_bfs;
+pub mod puzzles;
+pub mod related;
+pub mod scc;
+pub mod yen;
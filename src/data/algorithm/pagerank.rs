@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use log::debug;
+
+use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PageRankOptions {
+    /// Probability of following a link instead of jumping to a random page
+    pub damping: f64,
+    /// Maximum number of iterations
+    pub iterations: usize,
+    /// Stop once the scores change less than this in total (L1 norm) between iterations
+    pub tolerance: f64,
+}
+
+impl Default for PageRankOptions {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            iterations: 100,
+            tolerance: 1e-9,
+        }
+    }
+}
+
+/// PageRank of `pages` and all pages occurring in `links`. `resolve` returns the page a link to
+/// a page counts for: the page itself, the target of a redirect or `None` to drop the link.
+/// Pages resolving to another page get no rank and their own links are ignored. The scores sum
+/// up to 1, the rank of pages without links is spread evenly over all pages. Returns the scores
+/// and the number of iterations run.
+pub fn pagerank(
+    pages: impl Iterator<Item = i32>,
+    links: &LinkMap,
+    resolve: impl Fn(i32) -> Option<i32>,
+    options: PageRankOptions,
+    progress: ProgressBuilder,
+) -> (HashMap<i32, f64>, usize) {
+    // compact indices for fast iterations
+    let mut index: HashMap<i32, usize> = HashMap::new();
+    let mut ids = vec![];
+    let mut add = |id: i32| {
+        *index.entry(id).or_insert_with(|| {
+            ids.push(id);
+            ids.len() - 1
+        })
+    };
+
+    let is_page = |id: i32| resolve(id) == Some(id);
+
+    for id in pages.filter(|&id| is_page(id)) {
+        add(id);
+    }
+
+    let mut offsets = vec![0];
+    let mut sources = vec![];
+    let mut targets = vec![];
+    for (from, to) in links.iter().filter(|&(from, _)| is_page(from)) {
+        sources.push(add(from));
+        targets.extend(to.iter().filter_map(|&to| resolve(to)).map(&mut add));
+        offsets.push(targets.len());
+    }
+
+    let n = ids.len();
+    if n == 0 {
+        return (HashMap::new(), 0);
+    }
+
+    let mut out_degree = vec![0usize; n];
+    for (i, &source) in sources.iter().enumerate() {
+        out_degree[source] = offsets[i + 1] - offsets[i];
+    }
+
+    let progress = progress.with_len(options.iterations as u64).build();
+
+    let mut rank = vec![1.0 / n as f64; n];
+    let mut iterations = 0;
+
+    while iterations < options.iterations {
+        iterations += 1;
+
+        let dangling: f64 = (0..n)
+            .filter(|&i| out_degree[i] == 0)
+            .map(|i| rank[i])
+            .sum();
+        let base = (1.0 - options.damping) / n as f64 + options.damping * dangling / n as f64;
+
+        let mut next = vec![base; n];
+        for (i, &source) in sources.iter().enumerate() {
+            let share = options.damping * rank[source] / out_degree[source] as f64;
+            for &target in &targets[offsets[i]..offsets[i + 1]] {
+                next[target] += share;
+            }
+        }
+
+        let change: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+
+        progress.inc(1);
+
+        debug!("PageRank iteration {}: change {}", iterations, change);
+        if change < options.tolerance {
+            break;
+        }
+    }
+
+    progress.finish();
+
+    (ids.into_iter().zip(rank).collect(), iterations)
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::PageRankOptions;
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn cycle_is_uniform() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 1)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let (ranks, _) = super::pagerank(
+            std::iter::empty(),
+            &link_map,
+            Some,
+            PageRankOptions::default(),
+            ProgressBuilder::empty(),
+        );
+
+        for id in 1..=3 {
+            assert!((ranks[&id] - 1.0 / 3.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn hub_ranks_highest() {
+        // 2, 3 and 4 link to 1, 1 links back to 2, 5 has no links
+        let link_map = LinkMap::new_with_progress(
+            vec![(2, 1), (3, 1), (4, 1), (1, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let (ranks, iterations) = super::pagerank(
+            [1, 2, 3, 4, 5].into_iter(),
+            &link_map,
+            Some,
+            PageRankOptions::default(),
+            ProgressBuilder::empty(),
+        );
+
+        assert!(iterations > 1);
+        assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(ranks[&1] > ranks[&2]);
+        assert!(ranks[&2] > ranks[&3]);
+        assert!((ranks[&3] - ranks[&5]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn redirects_pass_on_their_links() {
+        // 3 is a redirect to 1, 4 only links to 1 through the redirect
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 1), (4, 3)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let resolve = |id| Some(if id == 3 { 1 } else { id });
+        let (ranks, _) = super::pagerank(
+            [1, 2, 3, 4].into_iter(),
+            &link_map,
+            resolve,
+            PageRankOptions::default(),
+            ProgressBuilder::empty(),
+        );
+
+        let direct = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 1), (4, 1)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+        let (expected, _) = super::pagerank(
+            [1, 2, 4].into_iter(),
+            &direct,
+            Some,
+            PageRankOptions::default(),
+            ProgressBuilder::empty(),
+        );
+
+        assert!(!ranks.contains_key(&3));
+        for id in [1, 2, 4] {
+            assert!((ranks[&id] - expected[&id]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn respects_iterations() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(2, 1), (3, 1), (1, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let options = PageRankOptions {
+            iterations: 2,
+            tolerance: 0.0,
+            ..Default::default()
+        };
+        let (_, iterations) = super::pagerank(
+            std::iter::empty(),
+            &link_map,
+            Some,
+            options,
+            ProgressBuilder::empty(),
+        );
+
+        assert_eq!(iterations, 2);
+    }
+}
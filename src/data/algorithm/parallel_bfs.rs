@@ -0,0 +1,262 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use log::debug;
+
+use crate::data::{
+    algorithm::limits::{Limit, LimitTracker, SearchLimits, SearchResult},
+    maps::link_map::LinkMap,
+};
+
+// frontiers smaller than this per thread are expanded on the calling thread
+const MIN_CHUNK_SIZE: usize = 256;
+
+/// Set of page ids that can be filled from multiple threads
+struct AtomicBitSet {
+    words: Vec<AtomicU64>,
+}
+
+impl AtomicBitSet {
+    fn new(max_id: i32) -> Self {
+        let len = (max_id.max(0) as usize) / 64 + 1;
+        Self {
+            words: (0..len).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    /// Adds `id` to the set, returns true if it was not in the set before
+    fn insert(&self, id: i32) -> bool {
+        let (word, bit) = (id as usize / 64, 1 << (id as usize % 64));
+        self.words[word].fetch_or(bit, Ordering::Relaxed) & bit == 0
+    }
+}
+
+/// Expands `frontier` and returns the newly visited pages as `(page, predecessor)`. Stops early
+/// once any thread reached `end`.
+fn expand(
+    frontier: &[i32],
+    end: i32,
+    links: &LinkMap,
+    visited: &AtomicBitSet,
+    found: &AtomicBool,
+) -> Vec<(i32, i32)> {
+    let mut next = vec![];
+    for &at in frontier {
+        if found.load(Ordering::Relaxed) {
+            break;
+        }
+
+        for &neighbor in links.get(at).into_iter().flatten() {
+            if visited.insert(neighbor) {
+                next.push((neighbor, at));
+
+                if neighbor == end {
+                    found.store(true, Ordering::Relaxed);
+                    return next;
+                }
+            }
+        }
+    }
+
+    next
+}
+
+fn rebuild_path(predecessors: &HashMap<i32, i32>, start: i32, end: i32) -> Vec<i32> {
+    let mut path = vec![end];
+    let mut at = end;
+    while at != start {
+        at = predecessors[&at];
+        path.push(at);
+    }
+    path.reverse();
+
+    path
+}
+
+/// Level-synchronous BFS that splits every frontier across `threads` worker threads. Returns a
+/// path of the same length as [`super::bfs::find_shortest_path`], but which one of several
+/// shortest paths is returned depends on the thread timing. Limits are checked between levels.
+pub fn search(
+    start: i32,
+    end: i32,
+    links: &LinkMap,
+    threads: usize,
+    limits: &SearchLimits,
+) -> SearchResult {
+    if start == end {
+        return SearchResult::Found(vec![start]);
+    }
+
+    let max_id = links.max_id();
+    if start > max_id || end > max_id || start < 0 || end < 0 {
+        return SearchResult::NoPath;
+    }
+
+    let mut tracker = LimitTracker::start(limits);
+
+    let visited = AtomicBitSet::new(max_id);
+    let found = AtomicBool::new(false);
+    visited.insert(start);
+
+    let mut predecessors = HashMap::new();
+    let mut frontier = vec![start];
+    let mut depth = 0;
+
+    while !frontier.is_empty() {
+        if !tracker.depth_allowed(depth) {
+            return SearchResult::LimitReached(Limit::Depth);
+        }
+
+        if let Some(stop) = tracker.expand(frontier.len() as u64) {
+            return stop;
+        }
+
+        depth += 1;
+
+        let chunk_size = frontier.len().div_ceil(threads.max(1)).max(MIN_CHUNK_SIZE);
+
+        let next = if frontier.len() <= chunk_size {
+            expand(&frontier, end, links, &visited, &found)
+        } else {
+            std::thread::scope(|s| {
+                let handles = frontier
+                    .chunks(chunk_size)
+                    .map(|chunk| s.spawn(|| expand(chunk, end, links, &visited, &found)))
+                    .collect::<Vec<_>>();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("Error joining thread"))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        frontier = Vec::with_capacity(next.len());
+        for (page, predecessor) in next {
+            predecessors.insert(page, predecessor);
+            frontier.push(page);
+        }
+
+        if found.load(Ordering::Relaxed) {
+            debug!("Found path after {} levels", depth);
+            return SearchResult::Found(rebuild_path(&predecessors, start, end));
+        }
+    }
+
+    SearchResult::NoPath
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::{algorithm::bfs, maps::link_map::LinkMap},
+        indication::ProgressBuilder,
+    };
+    use proptest::prelude::*;
+
+    /// Random graph with `pages` pages and `links_per_page` links each (deterministic xorshift)
+    fn generated_graph(pages: i32, links_per_page: usize) -> LinkMap {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % pages as u64) as i32
+        };
+
+        let links = (0..pages)
+            .flat_map(|from| (0..links_per_page).map(move |_| from))
+            .map(|from| (from, next()))
+            .collect();
+
+        LinkMap::new_with_progress(links, ProgressBuilder::empty())
+    }
+
+    #[test]
+    fn start_is_end() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        assert_eq!(
+            super::search(1, 1, &link_map, 4, &Default::default()).path(),
+            Some(vec![1])
+        );
+    }
+
+    #[test]
+    fn no_way() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        assert_eq!(
+            super::search(2, 1, &link_map, 4, &Default::default()).path(),
+            None
+        );
+        assert_eq!(
+            super::search(1, 100, &link_map, 4, &Default::default()).path(),
+            None
+        );
+    }
+
+    #[test]
+    fn same_length_on_generated_graph() {
+        let link_map = generated_graph(20_000, 8);
+
+        for (start, end) in [(0, 19_999), (17, 4_242), (12_345, 3)] {
+            let expected = bfs::find_shortest_path(start, end, &link_map);
+            let path = super::search(start, end, &link_map, 4, &Default::default()).path();
+
+            assert_eq!(path.map(|p| p.len()), expected.map(|p| p.len()));
+        }
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture benchmark`
+    #[test]
+    #[ignore]
+    fn benchmark_against_sequential() {
+        let link_map = generated_graph(2_000_000, 20);
+        let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+        // computed once per link map, not part of a query
+        link_map.max_id();
+
+        for (start, end) in [(0, 1_999_999), (42, 1_000_000), (123_456, 654_321)] {
+            let before = std::time::Instant::now();
+            let sequential = bfs::find_shortest_path(start, end, &link_map);
+            let sequential_time = before.elapsed();
+
+            let before = std::time::Instant::now();
+            let parallel =
+                super::search(start, end, &link_map, threads, &Default::default()).path();
+            let parallel_time = before.elapsed();
+
+            assert_eq!(parallel.map(|p| p.len()), sequential.map(|p| p.len()));
+            println!(
+                "{start} > {end}: sequential {:?}, parallel ({threads} threads) {:?}",
+                sequential_time, parallel_time
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn same_length_as_bfs(
+            links in prop::collection::vec((0..30, 0..30), 0..120),
+            start in 0..30,
+            end in 0..30,
+            threads in 1..4usize,
+        ) {
+            let link_map = LinkMap::new_with_progress(links.into_iter().collect(), ProgressBuilder::empty());
+
+            let expected = bfs::find_shortest_path(start, end, &link_map);
+            let path = super::search(start, end, &link_map, threads, &Default::default()).path();
+
+            prop_assert_eq!(path.map(|p| p.len()), expected.map(|p| p.len()));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+use rand::{seq::IndexedRandom, Rng};
+
+use crate::{
+    data::{
+        algorithm::{
+            all_paths, bfs,
+            layers::{self, Direction},
+        },
+        maps::link_map::LinkMap,
+    },
+    indication::ProgressBuilder,
+};
+
+/// A start and target page exactly `solution.len() - 1` links apart
+#[derive(Debug, PartialEq)]
+pub struct Puzzle {
+    pub start: i32,
+    pub target: i32,
+    /// One of the shortest paths
+    pub solution: Vec<i32>,
+    /// Number of different shortest paths
+    pub shortest_paths: u64,
+}
+
+/// Samples up to `count` distinct puzzles whose shortest path has exactly `distance` links. Start
+/// and target are drawn from `pages`, intermediate pages are not restricted. Gives up after
+/// `attempts` start pages, as far away targets may not exist for every start.
+pub fn generate(
+    pages: &[i32],
+    distance: u32,
+    count: usize,
+    attempts: usize,
+    links: &LinkMap,
+    rng: &mut impl Rng,
+    progress: ProgressBuilder,
+) -> Vec<Puzzle> {
+    let progress = progress.with_len(count as u64).build();
+
+    let eligible = pages.iter().copied().collect::<HashSet<_>>();
+    let mut seen = HashSet::new();
+    let mut puzzles = vec![];
+
+    for _ in 0..attempts {
+        if puzzles.len() >= count {
+            break;
+        }
+
+        let Some(&start) = pages.choose(rng) else {
+            break;
+        };
+
+        let layers = layers::distance_layers(start, Direction::Forward, Some(distance), links);
+        let targets = layers
+            .get(distance as usize)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|target| eligible.contains(target) && !seen.contains(&(start, *target)))
+            .collect::<Vec<_>>();
+
+        let Some(&target) = targets.choose(rng) else {
+            continue;
+        };
+        seen.insert((start, target));
+
+        let solution =
+            bfs::find_shortest_path(start, target, links).expect("target is in a BFS layer");
+        let shortest_paths = all_paths::find_all_shortest_paths(start, target, links)
+            .map_or(0, |paths| paths.count());
+
+        puzzles.push(Puzzle {
+            start,
+            target,
+            solution,
+            shortest_paths,
+        });
+        progress.inc(1);
+    }
+
+    progress.finish();
+
+    puzzles
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::generate;
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+    #[allow(unused_imports)]
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn exact_distance() {
+        // 1 -> 2 -> 3 -> 4, 1 -> 5 -> 3, 6 -> 7
+        let links = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 3), (6, 7)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let puzzles = generate(
+            &[1, 2, 3, 4, 6, 7],
+            3,
+            5,
+            100,
+            &links,
+            &mut rng,
+            ProgressBuilder::empty(),
+        );
+
+        // only 1 -> 4 is 3 links apart, 5 is not eligible but may be on the solution
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].start, 1);
+        assert_eq!(puzzles[0].target, 4);
+        assert_eq!(puzzles[0].solution.len(), 4);
+        assert_eq!(puzzles[0].shortest_paths, 2);
+
+        let puzzles = generate(
+            &[1, 2, 3, 4, 6, 7],
+            1,
+            3,
+            100,
+            &links,
+            &mut rng,
+            ProgressBuilder::empty(),
+        );
+        assert_eq!(puzzles.len(), 3);
+        for puzzle in puzzles {
+            assert_eq!(puzzle.solution, vec![puzzle.start, puzzle.target]);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::data::maps::link_map::LinkMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// Pages linked from the same pages (co-citation)
+    CoCitation,
+    /// Pages linking to the same pages (bibliographic coupling)
+    Coupling,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Similarity {
+    /// Shared neighbors / all neighbors of both pages
+    Jaccard,
+    /// Sum of 1 / ln(degree) over the shared neighbors, rare neighbors count more
+    AdamicAdar,
+}
+
+/// Distinct pages linking to `id` (co-citation) or linked from `id` (coupling)
+fn neighbors(id: i32, relation: Relation, links: &LinkMap) -> HashSet<i32> {
+    let neighbors = match relation {
+        Relation::CoCitation => links.backlinks(id),
+        Relation::Coupling => links.get(id),
+    };
+    neighbors.into_iter().flatten().copied().collect()
+}
+
+/// Pages sharing a neighbor with `id`, reached from the shared neighbor `via`
+fn candidates(via: i32, relation: Relation, links: &LinkMap) -> HashSet<i32> {
+    neighbors(
+        via,
+        match relation {
+            Relation::CoCitation => Relation::Coupling,
+            Relation::Coupling => Relation::CoCitation,
+        },
+        links,
+    )
+}
+
+/// The `n` pages most similar to `id`, highest score first. Both relations need backlinks.
+pub fn related(
+    id: i32,
+    relation: Relation,
+    similarity: Similarity,
+    n: usize,
+    links: &LinkMap,
+) -> Vec<(i32, f64)> {
+    assert!(links.has_backlinks(), "related pages need backlinks");
+
+    let own = neighbors(id, relation, links);
+
+    // candidate -> (shared neighbors, Adamic-Adar score)
+    let mut shared: HashMap<i32, (usize, f64)> = HashMap::new();
+    for &via in &own {
+        let candidates = candidates(via, relation, links);
+        // the candidates of a shared neighbor always include `id`, so there are at least 2
+        let weight = 1.0 / (candidates.len().max(2) as f64).ln();
+
+        for candidate in candidates {
+            if candidate == id {
+                continue;
+            }
+            let entry = shared.entry(candidate).or_default();
+            entry.0 += 1;
+            entry.1 += weight;
+        }
+    }
+
+    let mut scores = shared
+        .into_iter()
+        .map(|(candidate, (count, adamic_adar))| {
+            let score = match similarity {
+                Similarity::Jaccard => {
+                    let union = own.len() + neighbors(candidate, relation, links).len() - count;
+                    count as f64 / union as f64
+                }
+                Similarity::AdamicAdar => adamic_adar,
+            };
+            (candidate, score)
+        })
+        .collect::<Vec<_>>();
+
+    scores.sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scores.truncate(n);
+
+    scores
+}
+
+#[cfg(test)]
+mod test {
+    use super::{related, Relation, Similarity};
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    fn links() -> LinkMap {
+        // 1 and 2 both link to 10 and 11, 3 links to 11 and 12
+        // 4 links to 1 and 2, 5 links to 1, 2 and 3
+        let mut links = LinkMap::new_with_progress(
+            vec![
+                (1, 10),
+                (1, 11),
+                (2, 10),
+                (2, 11),
+                (2, 11),
+                (3, 11),
+                (3, 12),
+                (4, 1),
+                (4, 2),
+                (5, 1),
+                (5, 2),
+                (5, 3),
+            ]
+            .into_iter()
+            .collect(),
+            ProgressBuilder::empty(),
+        );
+        links.build_backlinks(ProgressBuilder::empty());
+        links
+    }
+
+    #[test]
+    fn coupling() {
+        let links = links();
+
+        let result = related(1, Relation::Coupling, Similarity::Jaccard, 10, &links);
+        assert_eq!(result, vec![(2, 1.0), (3, 1.0 / 3.0)]);
+
+        let result = related(1, Relation::Coupling, Similarity::AdamicAdar, 1, &links);
+        // 10 is linked from 2 pages, 11 from 3
+        assert_eq!(result, vec![(2, 1.0 / 2f64.ln() + 1.0 / 3f64.ln())]);
+    }
+
+    #[test]
+    fn co_citation() {
+        let links = links();
+
+        let result = related(1, Relation::CoCitation, Similarity::Jaccard, 10, &links);
+        assert_eq!(result, vec![(2, 1.0), (3, 0.5)]);
+
+        let result = related(3, Relation::CoCitation, Similarity::AdamicAdar, 10, &links);
+        // 5 links to 3 pages
+        assert_eq!(result, vec![(1, 1.0 / 3f64.ln()), (2, 1.0 / 3f64.ln())]);
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+/// Strongly connected components of all pages that have or receive links (iterative Tarjan).
+/// Returns page -> component id and the number of components. Components are numbered in reverse
+/// topological order: a component only links to components with a smaller id.
+pub fn strongly_connected_components(
+    links: &LinkMap,
+    progress: ProgressBuilder,
+) -> (HashMap<i32, u32>, u32) {
+    let mut pages = links
+        .iter()
+        .flat_map(|(from, to)| std::iter::once(from).chain(to.iter().copied()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    pages.sort_unstable();
+
+    let progress = progress.with_len(pages.len() as u64).build();
+
+    let mut index: HashMap<i32, u32> = HashMap::with_capacity(pages.len());
+    let mut lowlink: HashMap<i32, u32> = HashMap::with_capacity(pages.len());
+    let mut on_stack: HashSet<i32> = HashSet::new();
+    let mut stack: Vec<i32> = vec![];
+
+    let mut component: HashMap<i32, u32> = HashMap::with_capacity(pages.len());
+    let mut components = 0;
+
+    for root in pages {
+        if index.contains_key(&root) {
+            continue;
+        }
+
+        // (page, index of the next link to follow) instead of recursion
+        let mut call_stack = vec![(root, 0)];
+        index.insert(root, index.len() as u32);
+        lowlink.insert(root, index[&root]);
+        stack.push(root);
+        on_stack.insert(root);
+
+        while let Some(&mut (page, ref mut next)) = call_stack.last_mut() {
+            let targets = links.get(page).unwrap_or(&[]);
+
+            if let Some(&target) = targets.get(*next) {
+                *next += 1;
+
+                if !index.contains_key(&target) {
+                    let target_index = index.len() as u32;
+                    index.insert(target, target_index);
+                    lowlink.insert(target, target_index);
+                    stack.push(target);
+                    on_stack.insert(target);
+                    call_stack.push((target, 0));
+                } else if on_stack.contains(&target) {
+                    let low = lowlink[&page].min(index[&target]);
+                    lowlink.insert(page, low);
+                }
+
+                continue;
+            }
+
+            call_stack.pop();
+
+            if let Some(&(parent, _)) = call_stack.last() {
+                let low = lowlink[&parent].min(lowlink[&page]);
+                lowlink.insert(parent, low);
+            }
+
+            if lowlink[&page] == index[&page] {
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(&member);
+                    component.insert(member, components);
+                    progress.inc(1);
+
+                    if member == page {
+                        break;
+                    }
+                }
+                components += 1;
+            }
+        }
+    }
+
+    progress.finish();
+
+    (component, components)
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn components() {
+        // cycle 1->2->3->1, which links to the cycle 4<->5, which links to 6
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (5, 6)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let (component, count) =
+            super::strongly_connected_components(&link_map, ProgressBuilder::empty());
+
+        assert_eq!(count, 3);
+        assert_eq!(component[&1], component[&2]);
+        assert_eq!(component[&1], component[&3]);
+        assert_eq!(component[&4], component[&5]);
+        assert_ne!(component[&1], component[&4]);
+
+        // reverse topological order
+        assert!(component[&6] < component[&4]);
+        assert!(component[&4] < component[&1]);
+    }
+
+    #[test]
+    fn long_chain_does_not_overflow() {
+        let link_map = LinkMap::new_with_progress(
+            (0..200_000).map(|i| (i, i + 1)).collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let (component, count) =
+            super::strongly_connected_components(&link_map, ProgressBuilder::empty());
+
+        assert_eq!(count, 200_001);
+        assert!(component[&200_000] < component[&0]);
+    }
+}
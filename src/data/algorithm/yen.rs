@@ -0,0 +1,150 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use log::debug;
+
+use crate::data::{algorithm::bfs, maps::link_map::LinkMap};
+
+/// Finds up to `k` loopless paths from `start` to `end` ordered by length (Yen's algorithm).
+/// Paths of equal length are ordered by their page ids.
+pub fn k_shortest_paths(start: i32, end: i32, k: usize, links: &LinkMap) -> Vec<Vec<i32>> {
+    if k == 0 {
+        return vec![];
+    }
+
+    let mut paths = match bfs::find_shortest_path(start, end, links) {
+        Some(path) => vec![path],
+        None => return vec![],
+    };
+
+    let mut candidates = BinaryHeap::new();
+    let mut seen = HashSet::from([paths[0].clone()]);
+
+    while paths.len() < k {
+        let previous = paths.last().unwrap();
+
+        for spur_index in 0..previous.len() - 1 {
+            let spur = previous[spur_index];
+            let root = &previous[..=spur_index];
+
+            // links already used by a found path sharing this root may not be taken again
+            let removed_links: HashSet<(i32, i32)> = paths
+                .iter()
+                .filter(|path| path.len() > spur_index + 1 && &path[..=spur_index] == root)
+                .map(|path| (path[spur_index], path[spur_index + 1]))
+                .collect();
+            // the root may not be revisited, otherwise the path would contain a loop
+            let removed_pages: HashSet<i32> = root[..spur_index].iter().copied().collect();
+
+            let spur_path = bfs::find_shortest_path_where(spur, end, links, |from, to| {
+                !removed_pages.contains(&to) && !removed_links.contains(&(from, to))
+            });
+
+            if let Some(spur_path) = spur_path {
+                let mut path = root[..spur_index].to_vec();
+                path.extend(spur_path);
+
+                if seen.insert(path.clone()) {
+                    candidates.push(Reverse((path.len(), path)));
+                }
+            }
+        }
+
+        match candidates.pop() {
+            Some(Reverse((_, path))) => paths.push(path),
+            None => break,
+        }
+    }
+
+    debug!("Found {} of {} requested paths", paths.len(), k);
+
+    paths
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn start_is_end() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::k_shortest_paths(1, 1, 3, &link_map);
+
+        assert_eq!(paths, vec![vec![1]]);
+    }
+
+    #[test]
+    fn no_way() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::k_shortest_paths(2, 1, 3, &link_map);
+
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn direct_link_and_detour() {
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (3, 2)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::k_shortest_paths(1, 2, 5, &link_map);
+
+        assert_eq!(paths, vec![vec![1, 2], vec![1, 3, 2]]);
+    }
+
+    #[test]
+    fn ordered_by_length() {
+        // path over 1->2->3->4 and 1->5->4
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::k_shortest_paths(1, 4, 2, &link_map);
+
+        assert_eq!(paths, vec![vec![1, 5, 4], vec![1, 2, 3, 4]]);
+    }
+
+    #[test]
+    fn paths_are_loopless() {
+        // 2 and 3 link to each other, 1->2->3->4 and 1->3->4 are the only loopless paths
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 3), (2, 3), (3, 2), (3, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let paths = super::k_shortest_paths(1, 4, 10, &link_map);
+
+        assert_eq!(paths, vec![vec![1, 3, 4], vec![1, 2, 3, 4]]);
+    }
+
+    #[test]
+    fn limited_to_k() {
+        // path over 1->2->3->4 and 1->5->6->4
+        let link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4), (1, 5), (5, 6), (6, 4)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        assert_eq!(super::k_shortest_paths(1, 4, 1, &link_map).len(), 1);
+        assert_eq!(super::k_shortest_paths(1, 4, 2, &link_map).len(), 2);
+        assert!(super::k_shortest_paths(1, 4, 0, &link_map).is_empty());
+    }
+}
@@ -1,29 +1,628 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Read, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
 use serde::{Deserialize, Serialize};
 
-use crate::data::maps::{link_map::LinkMap, page_map::PageMap};
+use crate::{
+    data::{
+        mapped,
+        maps::{
+            component_map::ComponentMap,
+            landmark_map::{LandmarkMap, LandmarkSelection},
+            link_map::LinkMap,
+            page_map::PageMap,
+            rank_map::RankMap,
+        },
+    },
+    error::{Error, Result},
+};
+
+/// First bytes of every database file
+pub const MAGIC: &[u8; 4] = b"WLDB";
+/// First bytes of every database file in the memory-mapped format
+pub const MAPPED_MAGIC: &[u8; 4] = b"WLDM";
+/// Incremented whenever the header or the serialized database changes incompatibly
+pub const FORMAT_VERSION: u32 = 3;
+/// Length of the CRC32 of all preceding bytes stored at the end of every database file
+pub const CHECKSUM_LEN: usize = 4;
+
+/// How the database is stored on disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// CBOR, deserialized completely when loading
+    #[default]
+    Cbor,
+    /// Flat arrays that are memory-mapped and queried without deserialization
+    Mapped,
+}
+
+impl Format {
+    fn magic(self) -> &'static [u8; 4] {
+        match self {
+            Format::Cbor => MAGIC,
+            Format::Mapped => MAPPED_MAGIC,
+        }
+    }
+}
+
+/// The dump a database was derived from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Source {
+    /// e.g. `dewiki`
+    pub wiki: Option<String>,
+    /// e.g. `20240501`
+    pub dump_date: Option<String>,
+}
+
+/// Metadata stored in front of the database, readable without loading the database
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Header {
+    pub source: Source,
+    /// Unix time in seconds the file was written
+    pub created_at: u64,
+    pub backlinks: bool,
+    pub landmarks: usize,
+    /// How the landmarks were chosen, `None` without landmarks
+    #[serde(default)]
+    pub landmark_selection: Option<LandmarkSelection>,
+    pub components: bool,
+    pub ranks: bool,
+    /// Pages excluding redirects
+    pub pages: usize,
+    pub redirects: usize,
+    pub links: usize,
+    /// zstd level the database after the header was compressed with
+    #[serde(default)]
+    pub zstd_level: Option<i32>,
+}
+
+impl Header {
+    /// Reads the magic bytes, the format version and the header, leaving `reader` at the database
+    pub fn read(reader: &mut impl Read) -> Result<(Format, Header)> {
+        let mut magic = [0; 4];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| Error::NotADatabase)?;
+        let format = match &magic {
+            MAGIC => Format::Cbor,
+            MAPPED_MAGIC => Format::Mapped,
+            _ => return Err(Error::NotADatabase),
+        };
+
+        let mut version = [0; 4];
+        let mut length = [0; 8];
+        reader
+            .read_exact(&mut version)
+            .and_then(|_| reader.read_exact(&mut length))
+            .map_err(|e| Error::Corrupt(format!("truncated header: {e}")))?;
+
+        let version = u32::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            return Err(Error::Version {
+                found: version,
+                expected: FORMAT_VERSION,
+            });
+        }
+
+        let length = u64::from_le_bytes(length);
+        let header = ciborium::from_reader(reader.take(length))
+            .map_err(|e| Error::Corrupt(format!("invalid header: {}", describe_read_error(&e))))?;
+
+        Ok((format, header))
+    }
+
+    pub fn write(&self, format: Format, writer: &mut impl Write) -> std::io::Result<()> {
+        let mut header = vec![];
+        ciborium::into_writer(self, &mut header).map_err(std::io::Error::other)?;
+
+        writer.write_all(format.magic())?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&(header.len() as u64).to_le_bytes())?;
+        writer.write_all(&header)
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct Database {
     pub links: LinkMap,
     pub pages: PageMap,
+    #[serde(default)]
+    pub landmarks: Option<LandmarkMap>,
+    #[serde(default)]
+    pub components: Option<ComponentMap>,
+    #[serde(default)]
+    pub ranks: Option<RankMap>,
+    // stored in the header
+    #[serde(skip)]
+    pub source: Source,
+    // format the database was read from or should be written in
+    #[serde(skip)]
+    pub format: Format,
+    // zstd level to compress the CBOR format with, stored in the header
+    #[serde(skip)]
+    pub zstd_level: Option<i32>,
 }
 
 impl Database {
     pub fn new(links: LinkMap, pages: PageMap) -> Self {
-        Self { links, pages }
+        Self {
+            links,
+            pages,
+            landmarks: None,
+            components: None,
+            ranks: None,
+            source: Source::default(),
+            format: Format::default(),
+            zstd_level: None,
+        }
+    }
+
+    fn header(&self) -> Header {
+        let redirects = self.pages.redirects().count();
+
+        Header {
+            source: self.source.clone(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            backlinks: self.links.has_backlinks(),
+            landmarks: self
+                .landmarks
+                .as_ref()
+                .map_or(0, |landmarks| landmarks.landmarks().len()),
+            landmark_selection: self.landmarks.as_ref().map(LandmarkMap::selection),
+            components: self.components.is_some(),
+            ranks: self.ranks.is_some(),
+            pages: self.pages.len() - redirects,
+            redirects,
+            links: self.links.iter().map(|(_, targets)| targets.len()).sum(),
+            zstd_level: match self.format {
+                Format::Cbor => self.zstd_level,
+                Format::Mapped => None,
+            },
+        }
+    }
+
+    /// Writes the database in its format. The file is replaced atomically, an interrupted write
+    /// leaves an existing database untouched. Fails if the file exists and `overwrite` is false.
+    pub fn to_file(&self, outfile: &str, overwrite: bool) -> Result<()> {
+        let header = self.header();
+
+        write_atomically(outfile, overwrite, |writer| {
+            if self.format == Format::Mapped {
+                return mapped::write(self, &header, writer).map_err(Error::io(outfile));
+            }
+
+            header
+                .write(Format::Cbor, writer)
+                .map_err(Error::io(outfile))?;
+
+            match self.zstd_level {
+                Some(level) => {
+                    let mut encoder =
+                        zstd::Encoder::new(writer, level).map_err(Error::io(outfile))?;
+                    ciborium::into_writer(self, &mut encoder).map_err(write_error(outfile))?;
+                    encoder.finish().map_err(Error::io(outfile))?;
+                }
+                None => ciborium::into_writer(self, writer).map_err(write_error(outfile))?,
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Reads a database in either format, mapped databases are not loaded into memory and
+    /// compressed databases are detected from the header
+    pub fn from_file(infile: &str) -> Result<Database> {
+        let file = std::fs::File::open(infile).map_err(Error::io(infile))?;
+        let mut reader = std::io::BufReader::new(file);
+
+        let (format, header) = Header::read(&mut reader)?;
+        if format == Format::Mapped {
+            return mapped::open(infile, header);
+        }
+
+        let mut db: Database = match header.zstd_level {
+            Some(_) => {
+                let decoder = zstd::Decoder::with_buffer(reader).map_err(Error::io(infile))?;
+                ciborium::from_reader(decoder)
+            }
+            None => ciborium::from_reader(reader),
+        }
+        .map_err(|e| match e {
+            ciborium::de::Error::Io(e) if e.kind() != std::io::ErrorKind::UnexpectedEof => {
+                Error::io(infile)(e)
+            }
+            e => Error::Corrupt(describe_read_error(&e)),
+        })?;
+        db.source = header.source;
+        db.zstd_level = header.zstd_level;
+
+        Ok(db)
+    }
+
+    /// Reads the whole file and compares its content with the stored checksum
+    pub fn verify_checksum(infile: &str) -> Result<Checksum> {
+        // rejects other files before reading them completely
+        Self::read_header(infile)?;
+
+        let mut file = File::open(infile).map_err(Error::io(infile))?;
+        let len = file.metadata().map_err(Error::io(infile))?.len();
+        let content = len
+            .checked_sub(CHECKSUM_LEN as u64)
+            .ok_or_else(|| Error::Corrupt("file is truncated".to_string()))?;
+
+        let mut hasher = ChecksumWriter::new(std::io::sink());
+        std::io::copy(&mut (&mut file).take(content), &mut hasher).map_err(Error::io(infile))?;
+
+        let mut stored = [0; CHECKSUM_LEN];
+        file.read_exact(&mut stored).map_err(Error::io(infile))?;
+
+        Ok(Checksum {
+            stored: u32::from_le_bytes(stored),
+            computed: hasher.hasher.finalize(),
+        })
+    }
+
+    /// Reads only the header of a database file
+    pub fn read_header(infile: &str) -> Result<(Format, Header)> {
+        let file = std::fs::File::open(infile).map_err(Error::io(infile))?;
+        Header::read(&mut std::io::BufReader::new(file))
+    }
+}
+
+/// Writes `path` by writing a temporary file in the same directory, syncing it to disk and
+/// renaming it over `path`. Readers never see a partial file and mapped databases can be replaced
+/// while they are in use.
+fn write_atomically(
+    path: &str,
+    overwrite: bool,
+    write: impl FnOnce(&mut ChecksumWriter<BufWriter<&File>>) -> Result<()>,
+) -> Result<()> {
+    let directory = Path::new(path)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file = tempfile::NamedTempFile::new_in(directory).map_err(Error::io(path))?;
+
+    let mut writer = ChecksumWriter::new(BufWriter::new(file.as_file()));
+    write(&mut writer)?;
+    let checksum = writer.hasher.clone().finalize();
+    writer
+        .writer
+        .write_all(&checksum.to_le_bytes())
+        .and_then(|_| writer.writer.flush())
+        .map_err(Error::io(path))?;
+    drop(writer);
+
+    // temporary files are only readable by the owner, keep the permissions of a replaced file
+    let permissions = match std::fs::metadata(path) {
+        Ok(metadata) => Some(metadata.permissions()),
+        #[cfg(unix)]
+        Err(_) => {
+            use std::os::unix::fs::PermissionsExt;
+            Some(std::fs::Permissions::from_mode(0o644))
+        }
+        #[cfg(not(unix))]
+        Err(_) => None,
+    };
+    if let Some(permissions) = permissions {
+        file.as_file()
+            .set_permissions(permissions)
+            .map_err(Error::io(path))?;
+    }
+    file.as_file().sync_all().map_err(Error::io(path))?;
+
+    let persisted = if overwrite {
+        file.persist(path)
+    } else {
+        file.persist_noclobber(path)
+    };
+    match persisted {
+        Err(e) if e.error.kind() == std::io::ErrorKind::AlreadyExists => {
+            return Err(Error::Input(format!("{path} already exists")))
+        }
+        result => result.map_err(|e| Error::io(path)(e.error))?,
+    };
+
+    // the rename is only durable once the directory is synced
+    #[cfg(unix)]
+    File::open(directory)
+        .and_then(|directory| directory.sync_all())
+        .map_err(Error::io(path))?;
+
+    Ok(())
+}
+
+/// Computes the CRC32 of everything written through it
+struct ChecksumWriter<W: Write> {
+    writer: W,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
     }
+}
+
+/// Checksum stored at the end of a database file and the checksum of its content
+#[derive(Debug, Clone, Copy)]
+pub struct Checksum {
+    pub stored: u32,
+    pub computed: u32,
+}
+
+impl Checksum {
+    pub fn is_valid(&self) -> bool {
+        self.stored == self.computed
+    }
+}
+
+/// Describes why deserializing a database or a part of it failed
+pub fn describe_read_error(e: &ciborium::de::Error<std::io::Error>) -> String {
+    match e {
+        ciborium::de::Error::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            "file is truncated".to_string()
+        }
+        ciborium::de::Error::Io(e) => e.to_string(),
+        ciborium::de::Error::Syntax(offset) => format!("invalid data at byte {offset}"),
+        ciborium::de::Error::Semantic(_, message) => message.clone(),
+        ciborium::de::Error::RecursionLimitExceeded => "data is nested too deeply".to_string(),
+    }
+}
+
+/// Maps a serialization error while writing `path`, only I/O errors can occur for the database
+fn write_error(path: &str) -> impl FnOnce(ciborium::ser::Error<std::io::Error>) -> Error + '_ {
+    move |e| match e {
+        ciborium::ser::Error::Io(e) => Error::io(path)(e),
+        ciborium::ser::Error::Value(message) => Error::io(path)(std::io::Error::other(message)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Database, Format, Header, FORMAT_VERSION, MAGIC};
+    use crate::{
+        data::{
+            maps::{link_map::LinkMap, page_map::PageMap},
+            pages::Page,
+        },
+        error::Error,
+        indication::ProgressBuilder,
+    };
+    use std::{io::Write, time::Instant};
+
+    /// Database with the pages 1 to the largest id in `links`, titled `Page {id}`
+    fn database(links: &[(i32, i32)]) -> Database {
+        let max_id = links
+            .iter()
+            .map(|&(from, to)| from.max(to))
+            .max()
+            .unwrap_or(0);
+        let pages = (1..=max_id)
+            .map(|id| Page {
+                id,
+                title: format!("Page {id}"),
+                redirect: false,
+                length: 100,
+            })
+            .collect();
+
+        Database::new(
+            LinkMap::new_with_progress(links.iter().copied().collect(), ProgressBuilder::empty()),
+            PageMap::new_with_progress(pages, Default::default(), ProgressBuilder::empty()),
+        )
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let mut db = database(&[(1, 2), (2, 3)]);
+        db.source.wiki = Some("dewiki".to_string());
+
+        let mut file = vec![];
+        db.header().write(Format::Cbor, &mut file).unwrap();
+        file.extend_from_slice(b"rest");
+
+        let mut reader = file.as_slice();
+        let (format, header) = Header::read(&mut reader).unwrap();
+        assert_eq!(format, Format::Cbor);
+        assert_eq!(header.source.wiki.as_deref(), Some("dewiki"));
+        assert_eq!(header.pages, 3);
+        assert_eq!(header.links, 2);
+        assert!(!header.backlinks);
+        assert_eq!(reader, b"rest");
 
-    pub fn to_file(&self, outfile: &str) {
-        let file = std::fs::File::create(outfile).unwrap();
-        let writer = std::io::BufWriter::new(file);
+        let mut newer = file.clone();
+        newer[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            Header::read(&mut newer.as_slice()),
+            Err(Error::Version { found, .. }) if found == FORMAT_VERSION + 1
+        ));
 
-        ciborium::into_writer(self, writer).expect("Error writing db");
+        assert!(matches!(
+            Header::read(&mut &b"\xa5legacy cbor"[..]),
+            Err(Error::NotADatabase)
+        ));
+        assert_eq!(&file[..4], MAGIC);
     }
 
-    pub fn from_file(infile: &str) -> Database {
-        let file = std::fs::File::open(infile).unwrap();
-        let reader = std::io::BufReader::new(file);
+    #[test]
+    fn compressed_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("db");
+        let path = path.to_str().unwrap();
+
+        for zstd_level in [None, Some(3)] {
+            let mut db = database(&[(1, 3), (1, 2), (2, 3)]);
+            db.links.build_backlinks(ProgressBuilder::empty());
+            db.zstd_level = zstd_level;
+            db.to_file(path, true).unwrap();
+
+            let (_, header) = Database::read_header(path).unwrap();
+            assert_eq!(header.zstd_level, zstd_level);
+
+            let db = Database::from_file(path).unwrap();
+            assert_eq!(db.zstd_level, zstd_level);
+            assert_eq!(db.links.get(1), Some(&[2, 3][..]));
+            assert_eq!(db.links.backlinks(3), Some(&[1, 2][..]));
+            assert_eq!(db.pages.name_to_id("Page 2"), Some(2));
+        }
+    }
+
+    #[test]
+    fn atomic_writes() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("db");
+        let path = path.to_str().unwrap();
+
+        let db = database(&[(1, 2)]);
+        db.to_file(path, false).unwrap();
+        let written = std::fs::read(path).unwrap();
+
+        assert!(matches!(db.to_file(path, false), Err(Error::Input(_))));
+
+        // a failed write keeps the old file
+        let failed = super::write_atomically(path, true, |writer| {
+            writer.write_all(b"partial").unwrap();
+            Err(Error::Corrupt("interrupted".to_string()))
+        });
+        assert!(failed.is_err());
+        assert_eq!(std::fs::read(path).unwrap(), written);
+
+        db.to_file(path, true).unwrap();
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn checksums() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("db");
+        let path = path.to_str().unwrap();
+
+        for format in [Format::Cbor, Format::Mapped] {
+            let mut db = database(&[(1, 2), (2, 1)]);
+            db.format = format;
+            db.to_file(path, true).unwrap();
+            assert!(Database::verify_checksum(path).unwrap().is_valid());
+
+            let mut bytes = std::fs::read(path).unwrap();
+            let middle = bytes.len() / 2;
+            bytes[middle] ^= 0xff;
+            std::fs::write(path, &bytes).unwrap();
+            assert!(!Database::verify_checksum(path).unwrap().is_valid());
+        }
+    }
+
+    #[test]
+    fn missing_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("missing.db");
+
+        match Database::from_file(path.to_str().unwrap()) {
+            Err(Error::Io { source, .. }) => {
+                assert_eq!(source.kind(), std::io::ErrorKind::NotFound)
+            }
+            _ => panic!("expected an I/O error"),
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with --release"]
+    fn benchmark_formats() {
+        let (pages, links_per_page) = (500_000, 20);
+
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % pages as u64) as i32
+        };
+        let links = (0..pages)
+            .flat_map(|from| (0..links_per_page).map(move |_| from))
+            .map(|from| (from, next()))
+            .collect();
+        let titles = (0..pages)
+            .map(|id| Page {
+                id,
+                title: format!("Page_{id}"),
+                redirect: false,
+                length: 100,
+            })
+            .collect();
+
+        let mut db = Database::new(
+            LinkMap::new_with_progress(links, ProgressBuilder::empty()),
+            PageMap::new_with_progress(titles, Default::default(), ProgressBuilder::empty()),
+        );
+        db.links.build_backlinks(ProgressBuilder::empty());
+
+        // size of the links before they were varint encoded
+        let plain = |links: &mut dyn Iterator<Item = (i32, &[i32])>| {
+            let map = links
+                .map(|(from, to)| (from, to.to_vec()))
+                .collect::<std::collections::HashMap<_, _>>();
+            let mut bytes = vec![];
+            ciborium::into_writer(&map, &mut bytes).unwrap();
+            bytes.len()
+        };
+        let plain_size = plain(&mut db.links.iter()) + plain(&mut db.links.iter_backlinks());
+        let mut encoded = vec![];
+        ciborium::into_writer(&db.links, &mut encoded).unwrap();
+        println!(
+            "links and backlinks: {:.1} MB as plain CBOR maps, {:.1} MB varint encoded",
+            plain_size as f64 / 1e6,
+            encoded.len() as f64 / 1e6
+        );
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("db");
+        let path = path.to_str().unwrap();
+
+        for (name, format, zstd_level) in [
+            ("cbor", Format::Cbor, None),
+            ("cbor + zstd 3", Format::Cbor, Some(3)),
+            ("cbor + zstd 19", Format::Cbor, Some(19)),
+            ("mapped", Format::Mapped, None),
+        ] {
+            db.format = format;
+            db.zstd_level = zstd_level;
+
+            let before = Instant::now();
+            db.to_file(path, true).unwrap();
+            let write_time = before.elapsed();
+
+            let size = std::fs::metadata(path).unwrap().len();
+
+            let before = Instant::now();
+            let loaded = Database::from_file(path).unwrap();
+            let load_time = before.elapsed();
+            assert_eq!(loaded.links.get(42), db.links.get(42));
 
-        ciborium::from_reader(reader).expect("Error reading db")
+            println!(
+                "{name}: {:.1} MB, written in {write_time:?}, loaded in {load_time:?}",
+                size as f64 / 1e6
+            );
+        }
     }
 }
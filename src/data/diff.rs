@@ -0,0 +1,378 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::data::{
+    algorithm::{bidirectional, limits::SearchLimits},
+    database::{Database, Source},
+};
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Page {
+    pub id: i32,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Rename {
+    pub id: i32,
+    pub old_title: String,
+    pub new_title: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LinkChanges {
+    pub id: i32,
+    pub title: String,
+    /// Titles of the new link targets
+    pub added: Vec<String>,
+    /// Titles of the old link targets
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct RedirectChange {
+    pub id: i32,
+    pub title: String,
+    /// `None` if the page was no redirect
+    pub old_target: Option<String>,
+    /// `None` if the page is no redirect anymore
+    pub new_target: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PathChange {
+    pub start: String,
+    pub target: String,
+    /// Shortest path as titles, `None` if a page is missing or no path exists
+    pub old: Option<Vec<String>>,
+    pub new: Option<Vec<String>>,
+}
+
+/// Changes from an old to a new database, pages are matched by id
+#[derive(Debug, Serialize)]
+pub struct DatabaseDiff {
+    pub old_source: Source,
+    pub new_source: Source,
+    pub added_pages: Vec<Page>,
+    pub removed_pages: Vec<Page>,
+    pub renamed_pages: Vec<Rename>,
+    pub added_links: usize,
+    pub removed_links: usize,
+    /// Pages whose links changed, most changes first
+    pub link_changes: Vec<LinkChanges>,
+    pub redirect_changes: Vec<RedirectChange>,
+    /// Shortest paths between the requested `(start, target)` titles in both databases
+    pub paths: Vec<PathChange>,
+}
+
+/// Title of `id` or `#id` for ids without a page
+fn name(db: &Database, id: i32) -> String {
+    db.pages
+        .id_to_name(id)
+        .map_or_else(|| format!("#{id}"), |title| title.to_string())
+}
+
+/// Targets only in `new` and only in `old`, repeated targets count as often as they occur
+fn multiset_difference(old: &[i32], new: &[i32]) -> (Vec<i32>, Vec<i32>) {
+    // databases written before link lists were sorted can contain unsorted lists
+    let sorted = |targets: &[i32]| {
+        let mut targets = targets.to_vec();
+        targets.sort_unstable();
+        targets
+    };
+    let (old, new) = (sorted(old), sorted(new));
+
+    let (mut added, mut removed) = (vec![], vec![]);
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        match (old.get(i), new.get(j)) {
+            (Some(a), Some(b)) if a == b => (i, j) = (i + 1, j + 1),
+            (Some(&a), Some(&b)) if a < b => {
+                removed.push(a);
+                i += 1;
+            }
+            (Some(&a), None) => {
+                removed.push(a);
+                i += 1;
+            }
+            (_, Some(&b)) => {
+                added.push(b);
+                j += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    (added, removed)
+}
+
+fn shortest_path(db: &Database, start: &str, target: &str) -> Option<Vec<String>> {
+    let start = db.pages.resolve_by_title(start)?;
+    let target = db.pages.resolve_by_title(target)?;
+
+    let path =
+        bidirectional::search(start.id, target.id, &db.links, &SearchLimits::default()).path()?;
+    Some(path.into_iter().map(|id| name(db, id)).collect())
+}
+
+impl DatabaseDiff {
+    /// Compares the databases and the shortest paths between the `paths` titles
+    pub fn new(old: &Database, new: &Database, paths: &[(String, String)]) -> Self {
+        let mut added_pages = vec![];
+        let mut removed_pages = vec![];
+        let mut renamed_pages = vec![];
+
+        for (id, old_title) in old.pages.iter() {
+            match new.pages.id_to_name(id) {
+                None => removed_pages.push(Page {
+                    id,
+                    title: old_title.to_string(),
+                }),
+                Some(new_title) if new_title != old_title => renamed_pages.push(Rename {
+                    id,
+                    old_title: old_title.to_string(),
+                    new_title: new_title.to_string(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (id, title) in new.pages.iter() {
+            if old.pages.id_to_name(id).is_none() {
+                added_pages.push(Page {
+                    id,
+                    title: title.to_string(),
+                });
+            }
+        }
+
+        let (mut added_links, mut removed_links) = (0, 0);
+        let mut link_changes = vec![];
+        let mut compare = |id: i32, old_targets: &[i32], new_targets: &[i32]| {
+            if old_targets == new_targets {
+                return;
+            }
+            // links are counted with their multiplicity like by `LinkMap::update`
+            let (added, removed) = multiset_difference(old_targets, new_targets);
+            if added.is_empty() && removed.is_empty() {
+                return;
+            }
+            added_links += added.len();
+            removed_links += removed.len();
+
+            link_changes.push(LinkChanges {
+                id,
+                title: name(new, id),
+                added: added.into_iter().map(|to| name(new, to)).collect(),
+                removed: removed.into_iter().map(|to| name(old, to)).collect(),
+            });
+        };
+        for (from, old_targets) in old.links.iter() {
+            compare(from, old_targets, new.links.get(from).unwrap_or(&[]));
+        }
+        for (from, new_targets) in new.links.iter() {
+            if old.links.get(from).is_none() {
+                compare(from, &[], new_targets);
+            }
+        }
+        link_changes.sort_unstable_by_key(|changes| {
+            (
+                std::cmp::Reverse(changes.added.len() + changes.removed.len()),
+                changes.id,
+            )
+        });
+
+        let mut redirect_changes = vec![];
+        let redirect_ids = old
+            .pages
+            .redirects()
+            .chain(new.pages.redirects())
+            .map(|(from, _)| from)
+            .collect::<HashSet<_>>();
+        for id in redirect_ids {
+            let (old_target, new_target) =
+                (old.pages.id_to_redirect(id), new.pages.id_to_redirect(id));
+            if old_target != new_target {
+                redirect_changes.push(RedirectChange {
+                    id,
+                    title: new
+                        .pages
+                        .id_to_name(id)
+                        .map_or_else(|| name(old, id), |title| title.to_string()),
+                    old_target: old_target.map(|to| name(old, to)),
+                    new_target: new_target.map(|to| name(new, to)),
+                });
+            }
+        }
+
+        added_pages.sort_unstable_by_key(|page| page.id);
+        removed_pages.sort_unstable_by_key(|page| page.id);
+        renamed_pages.sort_unstable_by_key(|rename| rename.id);
+        redirect_changes.sort_unstable_by_key(|change| change.id);
+
+        let paths = paths
+            .iter()
+            .map(|(start, target)| PathChange {
+                start: start.clone(),
+                target: target.clone(),
+                old: shortest_path(old, start, target),
+                new: shortest_path(new, start, target),
+            })
+            .collect();
+
+        Self {
+            old_source: old.source.clone(),
+            new_source: new.source.clone(),
+            added_pages,
+            removed_pages,
+            renamed_pages,
+            added_links,
+            removed_links,
+            link_changes,
+            redirect_changes,
+            paths,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DatabaseDiff, LinkChanges, Page, PathChange, RedirectChange, Rename};
+    use crate::{
+        data::{
+            database::Database,
+            maps::{link_map::LinkMap, page_map::PageMap},
+            pages,
+            redirects::Redirect,
+        },
+        indication::ProgressBuilder,
+    };
+
+    fn database(
+        titles: &[(i32, &str)],
+        redirects: &[(i32, &str)],
+        links: &[(i32, i32)],
+    ) -> Database {
+        let pages = titles
+            .iter()
+            .map(|&(id, title)| pages::Page {
+                id,
+                title: title.to_string(),
+                redirect: redirects.iter().any(|&(from, _)| from == id),
+                length: 100,
+            })
+            .collect();
+        let redirects = redirects
+            .iter()
+            .map(|&(id, title)| Redirect {
+                id,
+                title: title.to_string(),
+            })
+            .collect();
+
+        Database::new(
+            LinkMap::new_with_progress(links.iter().copied().collect(), ProgressBuilder::empty()),
+            PageMap::new_with_progress(pages, redirects, ProgressBuilder::empty()),
+        )
+    }
+
+    #[test]
+    fn diff() {
+        let old = database(
+            &[
+                (1, "Linux"),
+                (2, "Torvalds"),
+                (3, "Helsinki"),
+                (4, "Kernel"),
+                (5, "Finnland"),
+            ],
+            &[(5, "Helsinki")],
+            &[(1, 2), (2, 3), (1, 4)],
+        );
+        let new = database(
+            &[
+                (1, "Linux"),
+                (2, "Linus_Torvalds"),
+                (3, "Helsinki"),
+                (5, "Finnland"),
+                (6, "1991"),
+            ],
+            &[],
+            &[(2, 1), (1, 3), (1, 2), (6, 1)],
+        );
+
+        let paths = [("Linux".to_string(), "Helsinki".to_string())];
+        let diff = DatabaseDiff::new(&old, &new, &paths);
+
+        assert_eq!(
+            diff.added_pages,
+            vec![Page {
+                id: 6,
+                title: "1991".to_string()
+            }]
+        );
+        assert_eq!(
+            diff.removed_pages,
+            vec![Page {
+                id: 4,
+                title: "Kernel".to_string()
+            }]
+        );
+        assert_eq!(
+            diff.renamed_pages,
+            vec![Rename {
+                id: 2,
+                old_title: "Torvalds".to_string(),
+                new_title: "Linus_Torvalds".to_string()
+            }]
+        );
+
+        assert_eq!((diff.added_links, diff.removed_links), (3, 2));
+        assert_eq!(
+            diff.link_changes[0],
+            LinkChanges {
+                id: 1,
+                title: "Linux".to_string(),
+                added: vec!["Helsinki".to_string()],
+                removed: vec!["Kernel".to_string()],
+            }
+        );
+        assert_eq!(diff.link_changes.len(), 3);
+
+        assert_eq!(
+            diff.redirect_changes,
+            vec![RedirectChange {
+                id: 5,
+                title: "Finnland".to_string(),
+                old_target: Some("Helsinki".to_string()),
+                new_target: None,
+            }]
+        );
+
+        assert_eq!(
+            diff.paths,
+            vec![PathChange {
+                start: "Linux".to_string(),
+                target: "Helsinki".to_string(),
+                old: Some(
+                    vec!["Linux", "Torvalds", "Helsinki"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                ),
+                new: Some(vec!["Linux".to_string(), "Helsinki".to_string()]),
+            }]
+        );
+
+        // 2 links to 1 twice now
+        let twice = database(
+            &[(1, "Linux"), (2, "Linus_Torvalds")],
+            &[],
+            &[(2, 1), (2, 1)],
+        );
+        let once = database(&[(1, "Linux"), (2, "Linus_Torvalds")], &[], &[(2, 1)]);
+        let repeated = DatabaseDiff::new(&once, &twice, &[]);
+        assert_eq!((repeated.added_links, repeated.removed_links), (1, 0));
+        assert_eq!(repeated.link_changes[0].added, vec!["Linux".to_string()]);
+    }
+}
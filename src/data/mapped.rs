@@ -0,0 +1,681 @@
+//! Flat database format that is memory-mapped and queried in place instead of deserialized.
+//!
+//! Every page id (pages and link endpoints) gets a row, rows are sorted by id. Per-row data is
+//! stored in arrays, links as compressed sparse rows. Layout of the file:
+//!
+//! ```text
+//! magic, format version, header length, header   (see `Header`)
+//! sections                                       (little endian arrays, 8 byte aligned)
+//! layout                                         (CBOR, section positions)
+//! layout length                                  (u64)
+//! checksum                                       (CRC32 of everything before, u32)
+//! ```
+
+use std::{fs::File, io::Write};
+
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{
+        database::{describe_read_error, Database, Format, Header, CHECKSUM_LEN},
+        maps::{component_map::ComponentMap, landmark_map::LandmarkMap, rank_map::RankMap},
+    },
+    error::{Error, Result},
+};
+
+/// Row of ids that are not in the file
+const NO_ROW: u32 = u32::MAX;
+/// Redirect of rows that are no redirects
+const NO_REDIRECT: i32 = -1;
+
+/// Types stored in sections, all valid for any bit pattern
+trait Plain: Copy {
+    const SIZE: usize = std::mem::size_of::<Self>();
+
+    fn write_le(self, writer: &mut impl Write) -> std::io::Result<()>;
+}
+
+macro_rules! plain {
+    ($($t:ty),*) => {
+        $(impl Plain for $t {
+            fn write_le(self, writer: &mut impl Write) -> std::io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+        })*
+    };
+}
+
+plain!(u8, i32, u32, u64);
+
+/// Byte offset and number of elements of an array in the file
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Section {
+    offset: u64,
+    len: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Layout {
+    /// Page id per row, sorted
+    ids: Section,
+    /// Row per page id, from 0 to the largest id
+    id_index: Section,
+    /// Start of the title per row in `titles`, empty for rows that are no pages
+    title_offsets: Section,
+    titles: Section,
+    /// Rows of all pages, sorted by title
+    title_index: Section,
+    redirects: Section,
+    lengths: Section,
+    /// Start of the links per row in `forward_targets`
+    forward_offsets: Section,
+    forward_targets: Section,
+    backward_offsets: Option<Section>,
+    backward_targets: Option<Section>,
+    /// CBOR encoded landmarks, components and ranks
+    extras: Section,
+    /// Number of rows that are pages
+    pages: u64,
+}
+
+#[derive(Serialize)]
+struct ExtrasRef<'a> {
+    landmarks: Option<&'a LandmarkMap>,
+    components: Option<&'a ComponentMap>,
+    ranks: Option<&'a RankMap>,
+}
+
+#[derive(Deserialize)]
+struct Extras {
+    landmarks: Option<LandmarkMap>,
+    components: Option<ComponentMap>,
+    ranks: Option<RankMap>,
+}
+
+/// Memory-mapped database file, shared by the link and page maps of a database
+#[derive(Debug)]
+pub struct MappedFile {
+    mmap: Mmap,
+    layout: Layout,
+}
+
+impl MappedFile {
+    fn bytes(&self, section: Section) -> &[u8] {
+        &self.mmap[section.offset as usize..][..section.len as usize]
+    }
+
+    fn slice<T: Plain>(&self, section: Section) -> &[T] {
+        let bytes = &self.mmap[section.offset as usize..][..section.len as usize * T::SIZE];
+        // SAFETY: bounds and alignment were checked when opening, the file is little endian
+        // like the host and T is valid for any bit pattern
+        unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast(), section.len as usize) }
+    }
+
+    // The accessors use checked indexing, a corrupt file gives missing links and pages instead of
+    // panics. `check_rows` finds such corruption, but reads most of the file.
+
+    fn row(&self, id: i32) -> Option<usize> {
+        let index = self.slice::<u32>(self.layout.id_index);
+        match index.get(usize::try_from(id).ok()?) {
+            Some(&row) if row != NO_ROW => Some(row as usize),
+            _ => None,
+        }
+    }
+
+    fn range(offsets: &[u64], row: usize) -> Option<std::ops::Range<usize>> {
+        let (start, end) = (*offsets.get(row)?, *offsets.get(row + 1)?);
+        (start <= end).then_some(start as usize..end as usize)
+    }
+
+    fn neighbors(&self, offsets: Section, targets: Section, id: i32) -> Option<&[i32]> {
+        let range = Self::range(self.slice(offsets), self.row(id)?)?;
+        self.slice(targets)
+            .get(range)
+            .filter(|targets| !targets.is_empty())
+    }
+
+    fn iter_neighbors(
+        &self,
+        offsets: Section,
+        targets: Section,
+    ) -> impl Iterator<Item = (i32, &[i32])> {
+        let (offsets, targets) = (self.slice::<u64>(offsets), self.slice::<i32>(targets));
+        self.slice::<i32>(self.layout.ids)
+            .iter()
+            .enumerate()
+            .filter_map(move |(row, &id)| Some((id, targets.get(Self::range(offsets, row)?)?)))
+            .filter(|(_, targets)| !targets.is_empty())
+    }
+
+    pub fn links(&self, from: i32) -> Option<&[i32]> {
+        self.neighbors(
+            self.layout.forward_offsets,
+            self.layout.forward_targets,
+            from,
+        )
+    }
+
+    pub fn iter_links(&self) -> impl Iterator<Item = (i32, &[i32])> {
+        self.iter_neighbors(self.layout.forward_offsets, self.layout.forward_targets)
+    }
+
+    pub fn has_backlinks(&self) -> bool {
+        self.layout.backward_offsets.is_some()
+    }
+
+    pub fn backlinks(&self, to: i32) -> Option<&[i32]> {
+        self.neighbors(
+            self.layout.backward_offsets?,
+            self.layout.backward_targets?,
+            to,
+        )
+    }
+
+    pub fn iter_backlinks(&self) -> impl Iterator<Item = (i32, &[i32])> {
+        let sections = self
+            .layout
+            .backward_offsets
+            .zip(self.layout.backward_targets);
+        sections
+            .into_iter()
+            .flat_map(|(offsets, targets)| self.iter_neighbors(offsets, targets))
+    }
+
+    /// Largest id with a row, `-1` if there are none
+    pub fn max_id(&self) -> i32 {
+        self.layout.id_index.len as i32 - 1
+    }
+
+    fn row_title(&self, row: usize) -> Option<&str> {
+        let range = Self::range(self.slice(self.layout.title_offsets), row)?;
+        if range.is_empty() {
+            return None;
+        }
+        std::str::from_utf8(self.bytes(self.layout.titles).get(range)?).ok()
+    }
+
+    pub fn title(&self, id: i32) -> Option<&str> {
+        self.row_title(self.row(id)?)
+    }
+
+    pub fn id(&self, title: &str) -> Option<i32> {
+        let rows = self.slice::<u32>(self.layout.title_index);
+        let index = rows
+            .binary_search_by(|&row| self.row_title(row as usize).unwrap_or("").cmp(title))
+            .ok()?;
+        self.slice::<i32>(self.layout.ids)
+            .get(rows[index] as usize)
+            .copied()
+    }
+
+    pub fn redirect(&self, id: i32) -> Option<i32> {
+        let redirect = *self
+            .slice::<i32>(self.layout.redirects)
+            .get(self.row(id)?)?;
+        (redirect != NO_REDIRECT).then_some(redirect)
+    }
+
+    pub fn length(&self, id: i32) -> Option<u32> {
+        let row = self.row(id)?;
+        self.row_title(row)?;
+        self.slice::<u32>(self.layout.lengths).get(row).copied()
+    }
+
+    pub fn iter_pages(&self) -> impl Iterator<Item = (i32, &str)> {
+        self.slice::<i32>(self.layout.ids)
+            .iter()
+            .enumerate()
+            .filter_map(|(row, &id)| Some((id, self.row_title(row)?)))
+    }
+
+    /// `(title, id)` in title order
+    pub fn iter_titles(&self) -> impl Iterator<Item = (&str, i32)> {
+        let ids = self.slice::<i32>(self.layout.ids);
+        self.slice::<u32>(self.layout.title_index)
+            .iter()
+            .filter_map(move |&row| Some((self.row_title(row as usize)?, *ids.get(row as usize)?)))
+    }
+
+    pub fn iter_redirects(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let ids = self.slice::<i32>(self.layout.ids);
+        self.slice::<i32>(self.layout.redirects)
+            .iter()
+            .enumerate()
+            .filter(|(_, &to)| to != NO_REDIRECT)
+            .map(move |(row, &to)| (ids[row], to))
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.layout.pages as usize
+    }
+}
+
+impl MappedFile {
+    /// Checks that all offsets and row numbers stay within their sections, i.e. that no links or
+    /// pages are lost to corruption. Reads most of the file.
+    pub fn check_rows(&self) -> Result<()> {
+        let layout = &self.layout;
+        let rows = layout.ids.len;
+
+        let csr = [
+            Some((layout.title_offsets, layout.titles)),
+            Some((layout.forward_offsets, layout.forward_targets)),
+            layout.backward_offsets.zip(layout.backward_targets),
+        ];
+        for (offsets, targets) in csr.into_iter().flatten() {
+            let offsets = self.slice::<u64>(offsets);
+            let increasing = offsets.windows(2).all(|pair| pair[0] <= pair[1]);
+            if !increasing || offsets.last().is_some_and(|&end| end > targets.len) {
+                return Err(Error::Corrupt("invalid offsets".to_string()));
+            }
+        }
+
+        let in_range = |&row: &u32| u64::from(row) < rows;
+        let id_index = self.slice::<u32>(layout.id_index);
+        let title_index = self.slice::<u32>(layout.title_index);
+        if !id_index.iter().all(|row| *row == NO_ROW || in_range(row))
+            || !title_index.iter().all(in_range)
+        {
+            return Err(Error::Corrupt("invalid row numbers".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Layout at the end of the file and where it starts
+fn read_layout(bytes: &[u8]) -> Result<(Layout, usize)> {
+    let invalid = || Error::Corrupt("file is truncated".to_string());
+
+    let end = bytes
+        .len()
+        .checked_sub(8 + CHECKSUM_LEN)
+        .ok_or_else(invalid)?;
+    let layout_len = u64::from_le_bytes(bytes[end..end + 8].try_into().unwrap()) as usize;
+    let layout_start = end.checked_sub(layout_len).ok_or_else(invalid)?;
+    let layout = ciborium::from_reader(&bytes[layout_start..end])
+        .map_err(|e| Error::Corrupt(format!("invalid layout: {}", describe_read_error(&e))))?;
+
+    Ok((layout, layout_start))
+}
+
+/// Maps a database file in the flat format, the optional maps are deserialized
+pub fn open(path: &str, header: Header) -> Result<Database> {
+    if cfg!(target_endian = "big") {
+        return Err("Mapped databases can only be read on little endian machines".into());
+    }
+
+    let file = File::open(path).map_err(Error::io(path))?;
+    // SAFETY: the file must not be modified while it is mapped, databases are only ever
+    // replaced by renaming a new file over them
+    let mmap = unsafe { Mmap::map(&file) }.map_err(Error::io(path))?;
+
+    let invalid = || Error::Corrupt("file is truncated".to_string());
+    let (layout, layout_start) = read_layout(&mmap)?;
+
+    let sections = [
+        (layout.ids, i32::SIZE),
+        (layout.id_index, u32::SIZE),
+        (layout.title_offsets, u64::SIZE),
+        (layout.titles, u8::SIZE),
+        (layout.title_index, u32::SIZE),
+        (layout.redirects, i32::SIZE),
+        (layout.lengths, u32::SIZE),
+        (layout.forward_offsets, u64::SIZE),
+        (layout.forward_targets, i32::SIZE),
+        (layout.extras, u8::SIZE),
+    ]
+    .into_iter()
+    .chain(layout.backward_offsets.map(|s| (s, u64::SIZE)))
+    .chain(layout.backward_targets.map(|s| (s, i32::SIZE)));
+
+    for (section, size) in sections {
+        let start = section.offset as usize;
+        let fits = (section.len as usize)
+            .checked_mul(size)
+            .and_then(|bytes| bytes.checked_add(start))
+            .is_some_and(|end| end <= layout_start);
+        let aligned = (mmap.as_ptr() as usize + start).is_multiple_of(size);
+        if !fits || !aligned {
+            return Err(invalid());
+        }
+    }
+
+    let rows = layout.ids.len;
+    let offsets = [
+        Some(layout.title_offsets),
+        Some(layout.forward_offsets),
+        layout.backward_offsets,
+    ];
+    if offsets.into_iter().flatten().any(|s| s.len != rows + 1)
+        || layout.redirects.len != rows
+        || layout.lengths.len != rows
+    {
+        return Err(Error::Corrupt("inconsistent sections".to_string()));
+    }
+
+    let file = MappedFile { mmap, layout };
+
+    let extras: Extras = ciborium::from_reader(file.bytes(file.layout.extras))
+        .map_err(|e| Error::Corrupt(describe_read_error(&e)))?;
+
+    let file = std::sync::Arc::new(file);
+    let mut db = Database::new(
+        crate::data::maps::link_map::LinkMap::mapped(file.clone()),
+        crate::data::maps::page_map::PageMap::mapped(file),
+    );
+    db.landmarks = extras.landmarks;
+    db.components = extras.components;
+    db.ranks = extras.ranks;
+    db.source = header.source;
+    db.format = Format::Mapped;
+
+    Ok(db)
+}
+
+/// Counts the written bytes to know the section offsets
+struct SectionWriter<W: Write> {
+    writer: W,
+    position: u64,
+}
+
+impl<W: Write> SectionWriter<W> {
+    fn section<T: Plain>(
+        &mut self,
+        values: impl IntoIterator<Item = T>,
+    ) -> std::io::Result<Section> {
+        let padding = self.position.next_multiple_of(8) - self.position;
+        self.writer.write_all(&vec![0; padding as usize])?;
+        self.position += padding;
+
+        let offset = self.position;
+        let mut len = 0;
+        for value in values {
+            value.write_le(&mut self.writer)?;
+            len += 1;
+        }
+        self.position += len * T::SIZE as u64;
+
+        Ok(Section { offset, len })
+    }
+}
+
+/// Writes `db` in the flat format
+pub fn write(db: &Database, header: &Header, writer: &mut impl Write) -> std::io::Result<()> {
+    let mut header_bytes = vec![];
+    header.write(Format::Mapped, &mut header_bytes)?;
+    writer.write_all(&header_bytes)?;
+
+    let mut out = SectionWriter {
+        writer: &mut *writer,
+        position: header_bytes.len() as u64,
+    };
+
+    let mut ids = db
+        .pages
+        .iter()
+        .map(|(id, _)| id)
+        .chain(
+            db.links
+                .iter()
+                .flat_map(|(from, to)| std::iter::once(from).chain(to.iter().copied())),
+        )
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let max_id = ids.last().copied().unwrap_or(-1);
+    let mut id_index = vec![NO_ROW; (max_id + 1).max(0) as usize];
+    for (row, &id) in ids.iter().enumerate() {
+        id_index[id as usize] = row as u32;
+    }
+
+    let titles = ids
+        .iter()
+        .map(|&id| db.pages.id_to_name(id).unwrap_or(""))
+        .collect::<Vec<_>>();
+    let mut title_index = (0..ids.len() as u32)
+        .filter(|&row| !titles[row as usize].is_empty())
+        .collect::<Vec<_>>();
+    title_index.sort_unstable_by_key(|&row| titles[row as usize]);
+
+    let offsets = |lengths: &mut dyn Iterator<Item = usize>| {
+        std::iter::once(0)
+            .chain(lengths.scan(0u64, |offset, len| {
+                *offset += len as u64;
+                Some(*offset)
+            }))
+            .collect::<Vec<_>>()
+    };
+
+    let layout_ids = out.section(ids.iter().copied())?;
+    let layout_id_index = out.section(id_index)?;
+    let title_offsets = out.section(offsets(&mut titles.iter().map(|title| title.len())))?;
+    let layout_titles = out.section(titles.iter().flat_map(|title| title.bytes()))?;
+    let layout_title_index = out.section(title_index.iter().copied())?;
+    let redirects = out.section(
+        ids.iter()
+            .map(|&id| db.pages.id_to_redirect(id).unwrap_or(NO_REDIRECT)),
+    )?;
+    let lengths = out.section(ids.iter().map(|&id| db.pages.length(id).unwrap_or(0)))?;
+
+    let forward_offsets =
+        out.section(offsets(&mut ids.iter().map(|&id| db.links.out_degree(id))))?;
+    let forward_targets = out.section(
+        ids.iter()
+            .flat_map(|&id| db.links.get(id).unwrap_or(&[]).iter().copied()),
+    )?;
+
+    let (backward_offsets, backward_targets) = if db.links.has_backlinks() {
+        let lengths = &mut ids
+            .iter()
+            .map(|&id| db.links.backlinks(id).map_or(0, |from| from.len()));
+        let offsets = out.section(offsets(lengths))?;
+        let targets = out.section(
+            ids.iter()
+                .flat_map(|&id| db.links.backlinks(id).unwrap_or(&[]).iter().copied()),
+        )?;
+        (Some(offsets), Some(targets))
+    } else {
+        (None, None)
+    };
+
+    let mut extras = vec![];
+    ciborium::into_writer(
+        &ExtrasRef {
+            landmarks: db.landmarks.as_ref(),
+            components: db.components.as_ref(),
+            ranks: db.ranks.as_ref(),
+        },
+        &mut extras,
+    )
+    .expect("Error serializing db");
+    let extras = out.section(extras)?;
+
+    let layout = Layout {
+        ids: layout_ids,
+        id_index: layout_id_index,
+        title_offsets,
+        titles: layout_titles,
+        title_index: layout_title_index,
+        redirects,
+        lengths,
+        forward_offsets,
+        forward_targets,
+        backward_offsets,
+        backward_targets,
+        extras,
+        pages: db.pages.len() as u64,
+    };
+    let mut layout_bytes = vec![];
+    ciborium::into_writer(&layout, &mut layout_bytes).expect("Error serializing layout");
+    writer.write_all(&layout_bytes)?;
+    writer.write_all(&(layout_bytes.len() as u64).to_le_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_layout;
+    use crate::{
+        data::{
+            database::{Database, Format},
+            maps::{link_map::LinkMap, page_map::PageMap, rank_map::RankMap},
+            pages::Page,
+            redirects::Redirect,
+        },
+        error::Error,
+        indication::ProgressBuilder,
+    };
+
+    fn database(backlinks: bool) -> Database {
+        let pages = [
+            (1, "Linux"),
+            (2, "Linus_Torvalds"),
+            (3, "Torvalds"),
+            (7, "1991"),
+        ]
+        .into_iter()
+        .map(|(id, title)| Page {
+            id,
+            title: title.to_string(),
+            redirect: id == 3,
+            length: id as u32 * 100,
+        })
+        .collect();
+        let redirects = vec![Redirect {
+            id: 3,
+            title: "Linus_Torvalds".to_string(),
+        }]
+        .into();
+        let pages = PageMap::new_with_progress(pages, redirects, ProgressBuilder::empty());
+
+        // 9 is a link target without a page
+        let mut links = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 7), (2, 1), (7, 1), (7, 9)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+        if backlinks {
+            links.build_backlinks(ProgressBuilder::empty());
+        }
+
+        let mut db = Database::new(links, pages);
+        db.ranks = Some(RankMap::new([(1, 0.5)].into()));
+        db.format = Format::Mapped;
+        db
+    }
+
+    #[test]
+    fn round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("mapped.db");
+        let path = path.to_str().unwrap();
+
+        for backlinks in [false, true] {
+            let original = database(backlinks);
+            original.to_file(path, true).unwrap();
+            let db = Database::from_file(path).unwrap();
+
+            assert_eq!(db.format, Format::Mapped);
+            assert_eq!(db.links.get(1), Some(&[2, 7][..]));
+            assert_eq!(db.links.get(9), None);
+            assert_eq!(db.links.get(3), None);
+            assert_eq!(db.links.get(-5), None);
+            assert_eq!(db.links.get(1000), None);
+            assert_eq!(db.links.out_degree(7), 2);
+            assert_eq!(db.links.max_id(), 9);
+            assert_eq!(db.links.has_backlinks(), backlinks);
+            assert_eq!(db.links.backlinks(1), backlinks.then_some(&[2, 7][..]));
+            assert_eq!(db.links.in_degree(9), backlinks.then_some(1));
+
+            let mut links = db.links.iter().collect::<Vec<_>>();
+            links.sort_unstable();
+            let mut expected = original.links.iter().collect::<Vec<_>>();
+            expected.sort_unstable();
+            assert_eq!(links, expected);
+            assert_eq!(
+                db.links.iter_backlinks().count(),
+                original.links.iter_backlinks().count()
+            );
+
+            assert_eq!(db.pages.len(), 4);
+            assert_eq!(db.pages.name_to_id("Linux"), Some(1));
+            assert_eq!(db.pages.name_to_id("1991"), Some(7));
+            assert_eq!(db.pages.name_to_id("Windows"), None);
+            assert_eq!(db.pages.id_to_name(2), Some("Linus_Torvalds"));
+            assert_eq!(db.pages.id_to_name(9), None);
+            assert_eq!(db.pages.id_to_redirect(3), Some(2));
+            assert_eq!(db.pages.id_to_redirect(2), None);
+            assert_eq!(db.pages.length(7), Some(700));
+            assert_eq!(db.pages.length(9), None);
+            assert_eq!(db.pages.redirects().collect::<Vec<_>>(), vec![(3, 2)]);
+            assert_eq!(
+                db.pages.resolve_by_title("Torvalds").map(|page| page.id),
+                Some(2)
+            );
+
+            let mut pages = db.pages.iter().collect::<Vec<_>>();
+            pages.sort_unstable();
+            assert_eq!(
+                pages,
+                vec![
+                    (1, "Linux"),
+                    (2, "Linus_Torvalds"),
+                    (3, "Torvalds"),
+                    (7, "1991")
+                ]
+            );
+
+            assert_eq!(db.ranks.as_ref().and_then(|ranks| ranks.get(1)), Some(0.5));
+
+            // rewriting a mapped database replaces the file instead of modifying the mapping
+            db.to_file(path, true).unwrap();
+            assert_eq!(db.pages.id_to_name(1), Some("Linux"));
+            assert_eq!(
+                Database::from_file(path).unwrap().links.get(2),
+                Some(&[1][..])
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("mapped.db");
+        let path = path.to_str().unwrap();
+
+        database(true).to_file(path, true).unwrap();
+        let bytes = std::fs::read(path).unwrap();
+        std::fs::write(path, &bytes[..bytes.len() - 20]).unwrap();
+
+        assert!(matches!(Database::from_file(path), Err(Error::Corrupt(_))));
+    }
+
+    #[test]
+    fn corrupt_offsets() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("mapped.db");
+        let path = path.to_str().unwrap();
+
+        database(false).to_file(path, true).unwrap();
+        let mut bytes = std::fs::read(path).unwrap();
+        let (layout, _) = read_layout(&bytes).unwrap();
+
+        // the end of the links of the first row now lies far behind the link targets
+        bytes[layout.forward_offsets.offset as usize + 8 + 7] ^= 0xff;
+        std::fs::write(path, &bytes).unwrap();
+
+        // opening only checks the layout, queries treat the corrupt row as missing
+        let db = Database::from_file(path).unwrap();
+        // the offset is also the start of the links of the second row
+        assert_eq!(db.links.get(1), None);
+        assert_eq!(db.links.get(2), None);
+        assert_eq!(db.links.get(7), Some(&[1, 9][..]));
+        assert_eq!(db.links.iter().count(), 1);
+
+        let file = db.links.mapped_file().unwrap();
+        assert!(matches!(file.check_rows(), Err(Error::Corrupt(_))));
+    }
+}
@@ -0,0 +1,194 @@
+//! Compact serialization of adjacency lists. All lists are stored in one byte string: the sources
+//! sorted and delta encoded, each list as zigzag encoded differences between consecutive targets.
+//! Every number is a LEB128 varint, so sorted lists of nearby ids take one or two bytes per link.
+//! `LinkMap` keeps its target lists sorted, backlinks are sorted anyway.
+
+use std::collections::HashMap;
+
+use serde::{de, Deserializer, Serializer};
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], position: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*position)?;
+        *position += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+fn encode(map: &HashMap<i32, Vec<i32>>) -> Vec<u8> {
+    let mut sources = map.keys().copied().collect::<Vec<_>>();
+    sources.sort_unstable();
+
+    let mut out = vec![];
+    write_varint(&mut out, sources.len() as u64);
+
+    let mut previous_source = 0i64;
+    for source in sources {
+        write_varint(&mut out, zigzag(source as i64 - previous_source));
+        previous_source = source as i64;
+
+        let targets = &map[&source];
+        write_varint(&mut out, targets.len() as u64);
+        let mut previous = 0i64;
+        for &target in targets {
+            write_varint(&mut out, zigzag(target as i64 - previous));
+            previous = target as i64;
+        }
+    }
+
+    out
+}
+
+fn decode(bytes: &[u8]) -> Option<HashMap<i32, Vec<i32>>> {
+    let mut position = 0;
+    let mut next = || read_varint(bytes, &mut position);
+
+    let count = next()? as usize;
+    // a corrupt count must not allocate huge amounts of memory
+    let mut map = HashMap::with_capacity(count.min(bytes.len()));
+
+    let mut source = 0i64;
+    for _ in 0..count {
+        source += unzigzag(next()?);
+        let len = next()? as usize;
+
+        let mut targets = Vec::with_capacity(len.min(bytes.len()));
+        let mut target = 0i64;
+        for _ in 0..len {
+            target += unzigzag(next()?);
+            targets.push(i32::try_from(target).ok()?);
+        }
+        map.insert(i32::try_from(source).ok()?, targets);
+    }
+
+    (position == bytes.len()).then_some(map)
+}
+
+struct Visitor;
+
+impl de::Visitor<'_> for Visitor {
+    type Value = HashMap<i32, Vec<i32>>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("encoded adjacency lists")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        decode(bytes).ok_or_else(|| E::custom("invalid adjacency lists"))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+        self.visit_bytes(&bytes)
+    }
+}
+
+pub fn serialize<S: Serializer>(
+    map: &HashMap<i32, Vec<i32>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(&encode(map))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<i32, Vec<i32>>, D::Error> {
+    deserializer.deserialize_byte_buf(Visitor)
+}
+
+/// Same encoding for optional adjacency lists like the backlinks
+pub mod optional {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    struct Bytes(Vec<u8>);
+
+    impl Serialize for Bytes {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Encoded(#[serde(deserialize_with = "super::deserialize")] HashMap<i32, Vec<i32>>);
+
+    pub fn serialize<S: Serializer>(
+        map: &Option<HashMap<i32, Vec<i32>>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        map.as_ref()
+            .map(|map| Bytes(super::encode(map)))
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<HashMap<i32, Vec<i32>>>, D::Error> {
+        Ok(Option::<Encoded>::deserialize(deserializer)?.map(|encoded| encoded.0))
+    }
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::{decode, encode, read_varint, unzigzag, write_varint, zigzag};
+    #[allow(unused_imports)]
+    use std::collections::HashMap;
+
+    #[test]
+    fn varints() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut bytes = vec![];
+            write_varint(&mut bytes, value);
+            let mut position = 0;
+            assert_eq!(read_varint(&bytes, &mut position), Some(value));
+            assert_eq!(position, bytes.len());
+        }
+
+        for value in [0, 1, -1, i32::MAX as i64, i32::MIN as i64 - i32::MAX as i64] {
+            assert_eq!(unzigzag(zigzag(value)), value);
+        }
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+    }
+
+    #[test]
+    fn round_trip() {
+        let map: HashMap<i32, Vec<i32>> = [
+            (5, vec![10, 2, 2, i32::MAX, i32::MIN]),
+            (1, vec![]),
+            (-3, vec![4]),
+            (i32::MAX, vec![1, 2, 3]),
+        ]
+        .into();
+
+        let bytes = encode(&map);
+        assert_eq!(decode(&bytes), Some(map));
+
+        // sorted lists of nearby ids take one byte per link
+        let sorted: HashMap<i32, Vec<i32>> = [(1, (100..200).collect())].into();
+        assert!(encode(&sorted).len() < 110);
+
+        assert_eq!(decode(&bytes[..bytes.len() - 1]), None);
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{algorithm::scc, maps::link_map::LinkMap},
+    indication::ProgressBuilder,
+};
+
+/// Strongly connected components of the link graph and the links between them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentMap {
+    // page -> component, pages without any links are not included
+    component: HashMap<i32, u32>,
+    // component -> number of pages
+    sizes: Vec<u32>,
+    // component -> components it links to (condensation DAG), only links to smaller ids
+    condensation: Vec<Vec<u32>>,
+}
+
+impl ComponentMap {
+    pub fn new_with_progress(links: &LinkMap, progress: ProgressBuilder) -> Self {
+        let (component, count) = scc::strongly_connected_components(links, progress);
+
+        let mut sizes = vec![0; count as usize];
+        for &c in component.values() {
+            sizes[c as usize] += 1;
+        }
+
+        let mut condensation = vec![vec![]; count as usize];
+        for (from, targets) in links.iter() {
+            let from = component[&from];
+            for to in targets {
+                let to = component[to];
+                if from != to {
+                    condensation[from as usize].push(to);
+                }
+            }
+        }
+        for targets in condensation.iter_mut() {
+            targets.sort_unstable();
+            targets.dedup();
+            targets.shrink_to_fit();
+        }
+
+        Self {
+            component,
+            sizes,
+            condensation,
+        }
+    }
+
+    pub fn component(&self, page: i32) -> Option<u32> {
+        self.component.get(&page).copied()
+    }
+
+    /// Number of pages in the component of `page`
+    pub fn component_size(&self, page: i32) -> Option<u32> {
+        self.component(page).map(|c| self.sizes[c as usize])
+    }
+
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Number of pages in the largest component
+    pub fn largest(&self) -> u32 {
+        self.sizes.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Whether `to` can be reached from `from`. Usually answered by the component ids alone,
+    /// otherwise by a search over the components instead of the pages.
+    pub fn reachable(&self, from: i32, to: i32) -> bool {
+        if from == to {
+            return true;
+        }
+
+        let (Some(from), Some(to)) = (self.component(from), self.component(to)) else {
+            return false;
+        };
+
+        if from == to {
+            return true;
+        }
+        // components only link to components with smaller ids
+        if from < to {
+            return false;
+        }
+
+        let mut visited = HashSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(at) = stack.pop() {
+            for &next in &self.condensation[at as usize] {
+                if next == to {
+                    return true;
+                }
+                if next > to && visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+#[test]
+fn reachability() {
+    // cycle 1->2->3->1, which links to the cycle 4<->5, which links to 6, 7 links to 1
+    let link_map = LinkMap::new_with_progress(
+        vec![
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (3, 4),
+            (4, 5),
+            (5, 4),
+            (5, 6),
+            (7, 1),
+            (8, 6),
+        ]
+        .into_iter()
+        .collect(),
+        ProgressBuilder::empty(),
+    );
+
+    let map = ComponentMap::new_with_progress(&link_map, ProgressBuilder::empty());
+
+    assert_eq!(map.len(), 5);
+    assert_eq!(map.largest(), 3);
+
+    assert!(map.reachable(1, 3));
+    assert!(map.reachable(3, 1));
+    assert!(map.reachable(1, 6));
+    assert!(map.reachable(7, 5));
+    assert!(!map.reachable(6, 1));
+    assert!(!map.reachable(4, 1));
+    assert!(!map.reachable(8, 1));
+    assert!(!map.reachable(1, 8));
+    assert!(!map.reachable(1, 100));
+    assert!(map.reachable(100, 100));
+}
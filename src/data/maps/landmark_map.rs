@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{algorithm::bfs, maps::link_map::LinkMap},
+    indication::ProgressBuilder,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LandmarkSelection {
+    /// Pages with the most incoming and outgoing links
+    Degree,
+    /// Starting with the highest degree page, the page farthest away from all chosen landmarks
+    #[default]
+    Farthest,
+}
+
+/// Precomputed distances from and to a few landmark pages, used for lower bounds of distances
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LandmarkMap {
+    // how the landmarks were chosen, to choose them the same way when updating the database
+    #[serde(default)]
+    selection: LandmarkSelection,
+    landmarks: Vec<i32>,
+    // per landmark: page -> distance from the landmark to the page
+    from_landmark: Vec<HashMap<i32, u16>>,
+    // per landmark: page -> distance from the page to the landmark
+    to_landmark: Vec<HashMap<i32, u16>>,
+}
+
+fn to_u16(distances: HashMap<i32, u32>) -> HashMap<i32, u16> {
+    distances
+        .into_iter()
+        .map(|(id, distance)| (id, distance.min(u16::MAX as u32) as u16))
+        .collect()
+}
+
+impl LandmarkMap {
+    /// Chooses up to `count` landmarks and computes their distances. Needs backlinks.
+    pub fn new_with_progress(
+        links: &LinkMap,
+        count: usize,
+        selection: LandmarkSelection,
+        progress: ProgressBuilder,
+    ) -> Self {
+        assert!(links.has_backlinks(), "Landmarks need backlinks");
+
+        let progress = progress.with_len(count as u64).build();
+
+        let mut degrees: HashMap<i32, usize> = HashMap::new();
+        for (id, targets) in links.iter().chain(links.iter_backlinks()) {
+            *degrees.entry(id).or_default() += targets.len();
+        }
+
+        // highest degree first, ties broken by id to stay deterministic
+        let mut by_degree = degrees.into_iter().collect::<Vec<_>>();
+        by_degree.sort_unstable_by_key(|&(id, degree)| (std::cmp::Reverse(degree), id));
+
+        let mut map = LandmarkMap {
+            selection,
+            landmarks: vec![],
+            from_landmark: vec![],
+            to_landmark: vec![],
+        };
+
+        // page -> smallest distance from any chosen landmark
+        let mut closest: HashMap<i32, u16> = HashMap::new();
+
+        while map.landmarks.len() < count {
+            let next = match selection {
+                LandmarkSelection::Degree => by_degree.get(map.landmarks.len()).map(|&(id, _)| id),
+                LandmarkSelection::Farthest if map.landmarks.is_empty() => {
+                    by_degree.first().map(|&(id, _)| id)
+                }
+                LandmarkSelection::Farthest => closest
+                    .iter()
+                    .filter(|&(_, &distance)| distance > 0)
+                    .max_by_key(|&(&id, &distance)| (distance, std::cmp::Reverse(id)))
+                    .map(|(&id, _)| id),
+            };
+
+            let Some(landmark) = next else {
+                break;
+            };
+
+            let from = to_u16(bfs::distances(landmark, |id| links.get(id)));
+            let to = to_u16(bfs::distances(landmark, |id| links.backlinks(id)));
+
+            if map.landmarks.is_empty() {
+                closest = from.clone();
+            } else {
+                for (id, distance) in closest.iter_mut() {
+                    *distance = (*distance).min(*from.get(id).unwrap_or(&u16::MAX));
+                }
+            }
+
+            map.landmarks.push(landmark);
+            map.from_landmark.push(from);
+            map.to_landmark.push(to);
+
+            progress.inc(1);
+        }
+
+        progress.finish();
+
+        map
+    }
+
+    pub fn landmarks(&self) -> &[i32] {
+        &self.landmarks
+    }
+
+    pub fn selection(&self) -> LandmarkSelection {
+        self.selection
+    }
+
+    /// Lower bound for the distance from `from` to `to` using the triangle inequality.
+    /// Returns `None` if the landmarks prove that `to` is not reachable from `from`.
+    pub fn lower_bound(&self, from: i32, to: i32) -> Option<u32> {
+        let mut bound = 0;
+
+        for i in 0..self.landmarks.len() {
+            // d(L, to) <= d(L, from) + d(from, to)
+            let landmark_distances = &self.from_landmark[i];
+            match (landmark_distances.get(&from), landmark_distances.get(&to)) {
+                (Some(&from), Some(&to)) => bound = bound.max(to as i32 - from as i32),
+                // the landmark reaches `from` and could therefore reach `to` over it
+                (Some(_), None) => return None,
+                _ => {}
+            }
+
+            // d(from, L) <= d(from, to) + d(to, L)
+            let landmark_distances = &self.to_landmark[i];
+            match (landmark_distances.get(&from), landmark_distances.get(&to)) {
+                (Some(&from), Some(&to)) => bound = bound.max(from as i32 - to as i32),
+                // `to` reaches the landmark, so `from` would reach it over `to`
+                (None, Some(_)) => return None,
+                _ => {}
+            }
+        }
+
+        Some(bound as u32)
+    }
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::{LandmarkMap, LandmarkSelection};
+    #[allow(unused_imports)]
+    use crate::{data::maps::link_map::LinkMap, indication::ProgressBuilder};
+
+    #[test]
+    fn selection() {
+        // 2 is the hub, 5 is the end of a chain
+        let mut link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 1), (2, 3), (3, 2), (2, 4), (4, 5)]
+                .into_iter()
+                .collect(),
+            ProgressBuilder::empty(),
+        );
+        link_map.build_backlinks(ProgressBuilder::empty());
+
+        let map = LandmarkMap::new_with_progress(
+            &link_map,
+            2,
+            LandmarkSelection::Degree,
+            ProgressBuilder::empty(),
+        );
+        assert_eq!(map.landmarks(), &[2, 1]);
+        assert_eq!(map.selection(), LandmarkSelection::Degree);
+
+        let map = LandmarkMap::new_with_progress(
+            &link_map,
+            2,
+            LandmarkSelection::Farthest,
+            ProgressBuilder::empty(),
+        );
+        assert_eq!(map.landmarks(), &[2, 5]);
+    }
+
+    #[test]
+    fn lower_bounds() {
+        // chain 1->2->3->4
+        let mut link_map = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3), (3, 4)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+        link_map.build_backlinks(ProgressBuilder::empty());
+
+        let map = LandmarkMap::new_with_progress(
+            &link_map,
+            2,
+            LandmarkSelection::Farthest,
+            ProgressBuilder::empty(),
+        );
+        assert_eq!(map.landmarks(), &[2, 4]);
+
+        assert_eq!(map.lower_bound(1, 4), Some(3));
+        assert_eq!(map.lower_bound(2, 4), Some(2));
+        assert_eq!(map.lower_bound(4, 4), Some(0));
+        assert_eq!(map.lower_bound(1, 3), Some(2));
+        // 4 does not link back
+        assert_eq!(map.lower_bound(4, 1), None);
+        assert_eq!(map.lower_bound(3, 2), None);
+    }
+}
@@ -1,11 +1,37 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{data::links::LinkResolved, indication::ProgressBuilder};
-use std::collections::{HashMap, VecDeque};
+use crate::{
+    data::{links::LinkResolved, mapped::MappedFile},
+    indication::ProgressBuilder,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, OnceLock},
+};
+
+/// Changes applied by `LinkMap::update`
+#[derive(Debug, Default, PartialEq)]
+pub struct LinkMapUpdate {
+    pub added: usize,
+    pub removed: usize,
+    /// Pages whose links changed
+    pub changed_pages: usize,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LinkMap {
+    // from -> [to]
+    #[serde(with = "super::adjacency")]
     forward: HashMap<i32, Vec<i32>>,
+    // to -> [from], only present if built with backlinks
+    #[serde(default, with = "super::adjacency::optional")]
+    backward: Option<HashMap<i32, Vec<i32>>>,
+    // largest page id in any link, computed on first use
+    #[serde(skip)]
+    max_id: OnceLock<i32>,
+    // set instead of the maps for databases in the mapped format
+    #[serde(skip)]
+    mapped: Option<Arc<MappedFile>>,
 }
 
 impl LinkMap {
@@ -49,13 +75,235 @@ impl LinkMap {
             }
         }
 
+        // the order of the links depends on the parser threads, sorted lists are deterministic
+        // and delta encode into fewer bytes
+        for targets in map.values_mut() {
+            targets.sort_unstable();
+        }
+
+        progress.finish();
+
+        LinkMap {
+            forward: map,
+            backward: None,
+            max_id: OnceLock::new(),
+            mapped: None,
+        }
+    }
+
+    /// Replaces the links with the links of a newer dump, the result is the same as building a
+    /// new map from them. The links are grouped by source and only the lists that changed are
+    /// replaced, existing backlinks are patched for those pages.
+    pub fn update(
+        &mut self,
+        mut links: VecDeque<LinkResolved>,
+        progress: ProgressBuilder,
+    ) -> LinkMapUpdate {
+        assert!(self.mapped.is_none(), "mapped link maps are read-only");
+
+        let progress = progress.with_len(links.len() as u64).build();
+        let mut update = LinkMapUpdate::default();
+
+        // sorted targets like in `new_with_progress`
+        let links = links.make_contiguous();
+        links.sort_unstable();
+
+        let removed = self
+            .forward
+            .keys()
+            .filter(|&&from| {
+                links
+                    .binary_search_by_key(&from, |&(from, _)| from)
+                    .is_err()
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        for from in removed {
+            let old = self.forward.remove(&from).unwrap();
+            self.patch_backlinks(from, &old, &[], &mut update);
+        }
+
+        for group in links.chunk_by(|a, b| a.0 == b.0) {
+            let from = group[0].0;
+            let unchanged = self.forward.get(&from).is_some_and(|old| {
+                old.len() == group.len() && old.iter().zip(group).all(|(&to, &(_, new))| to == new)
+            });
+
+            if !unchanged {
+                let targets = group.iter().map(|&(_, to)| to).collect::<Vec<_>>();
+                let old = self.forward.remove(&from).unwrap_or_default();
+                self.patch_backlinks(from, &old, &targets, &mut update);
+                self.forward.insert(from, targets);
+            }
+
+            progress.inc(group.len() as u64);
+        }
+
+        progress.finish();
+
+        self.max_id = OnceLock::new();
+
+        update
+    }
+
+    /// Moves `from` between the backlinks of the targets it links to more or less often now
+    fn patch_backlinks(&mut self, from: i32, old: &[i32], new: &[i32], update: &mut LinkMapUpdate) {
+        let mut counts: HashMap<i32, i64> = HashMap::new();
+        for &to in new {
+            *counts.entry(to).or_default() += 1;
+        }
+        for &to in old {
+            *counts.entry(to).or_default() -= 1;
+        }
+        counts.retain(|_, count| *count != 0);
+
+        if counts.is_empty() {
+            return;
+        }
+        update.changed_pages += 1;
+
+        for (to, count) in counts {
+            if count > 0 {
+                update.added += count as usize;
+            } else {
+                update.removed += -count as usize;
+            }
+
+            let Some(backward) = &mut self.backward else {
+                continue;
+            };
+            let sources = backward.entry(to).or_default();
+            for _ in 0..count {
+                let index = sources.partition_point(|&source| source < from);
+                sources.insert(index, from);
+            }
+            for _ in count..0 {
+                let index = sources
+                    .binary_search(&from)
+                    .expect("backlinks do not match the links");
+                sources.remove(index);
+            }
+            if sources.is_empty() {
+                backward.remove(&to);
+            }
+        }
+    }
+
+    /// Links read from a memory-mapped database file
+    pub fn mapped(file: Arc<MappedFile>) -> LinkMap {
+        LinkMap {
+            forward: HashMap::new(),
+            backward: None,
+            max_id: OnceLock::new(),
+            mapped: Some(file),
+        }
+    }
+
+    /// File of a database in the mapped format
+    pub fn mapped_file(&self) -> Option<&MappedFile> {
+        self.mapped.as_deref()
+    }
+
+    /// Builds the reverse adjacency from the forward links, replacing any existing backlinks
+    pub fn build_backlinks(&mut self, progress: ProgressBuilder) {
+        assert!(self.mapped.is_none(), "mapped link maps are read-only");
+
+        let progress = progress.with_len(self.forward.len() as u64).build();
+
+        let mut backward: HashMap<i32, Vec<i32>> = HashMap::new();
+
+        for (&from, targets) in &self.forward {
+            for &to in targets {
+                backward.entry(to).or_default().push(from);
+            }
+
+            progress.inc(1);
+        }
+
+        for sources in backward.values_mut() {
+            sources.sort_unstable();
+            sources.shrink_to_fit();
+        }
+
         progress.finish();
 
-        LinkMap { forward: map }
+        self.backward = Some(backward);
     }
 
-    pub fn get(&self, from: i32) -> Option<&Vec<i32>> {
-        self.forward.get(&from)
+    pub fn get(&self, from: i32) -> Option<&[i32]> {
+        match &self.mapped {
+            Some(file) => file.links(from),
+            None => self.forward.get(&from).map(Vec::as_slice),
+        }
+    }
+
+    /// Removes the backlinks again, e.g. if they were only needed temporarily
+    pub fn remove_backlinks(&mut self) {
+        assert!(self.mapped.is_none(), "mapped link maps are read-only");
+        self.backward = None;
+    }
+
+    /// All pages with outgoing links as `(from, [to])`
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (i32, &[i32])> + '_> {
+        match &self.mapped {
+            Some(file) => Box::new(file.iter_links()),
+            None => Box::new(self.forward.iter().map(|(&from, to)| (from, to.as_slice()))),
+        }
+    }
+
+    /// All pages with incoming links as `(to, [from])`, empty if backlinks were not built
+    pub fn iter_backlinks(&self) -> Box<dyn Iterator<Item = (i32, &[i32])> + '_> {
+        match &self.mapped {
+            Some(file) => Box::new(file.iter_backlinks()),
+            None => Box::new(
+                self.backward
+                    .iter()
+                    .flatten()
+                    .map(|(&to, from)| (to, from.as_slice())),
+            ),
+        }
+    }
+
+    /// Largest page id occurring in any link, `-1` if there are no links
+    pub fn max_id(&self) -> i32 {
+        *self.max_id.get_or_init(|| {
+            if let Some(file) = &self.mapped {
+                return file.max_id();
+            }
+
+            self.forward
+                .iter()
+                .flat_map(|(&from, to)| std::iter::once(from).chain(to.iter().copied()))
+                .max()
+                .unwrap_or(-1)
+        })
+    }
+
+    pub fn has_backlinks(&self) -> bool {
+        match &self.mapped {
+            Some(file) => file.has_backlinks(),
+            None => self.backward.is_some(),
+        }
+    }
+
+    /// Pages linking to `to`, `None` if there are none or backlinks were not built
+    pub fn backlinks(&self, to: i32) -> Option<&[i32]> {
+        match &self.mapped {
+            Some(file) => file.backlinks(to),
+            None => self.backward.as_ref()?.get(&to).map(Vec::as_slice),
+        }
+    }
+
+    pub fn out_degree(&self, from: i32) -> usize {
+        self.get(from).map_or(0, |targets| targets.len())
+    }
+
+    /// Number of pages linking to `to`, `None` if backlinks were not built
+    pub fn in_degree(&self, to: i32) -> Option<usize> {
+        if !self.has_backlinks() {
+            return None;
+        }
+        Some(self.backlinks(to).map_or(0, |sources| sources.len()))
     }
 }
 
@@ -65,7 +313,80 @@ fn new_link_map() {
 
     let map = LinkMap::new_with_progress(links, ProgressBuilder::empty());
 
-    assert_eq!(map.get(1), Some(&vec![2, 3]));
+    assert_eq!(map.get(1), Some(&[2, 3][..]));
     assert_eq!(map.get(2), None);
-    assert_eq!(map.get(3), Some(&vec![2]));
+    assert_eq!(map.get(3), Some(&[2][..]));
+
+    assert!(!map.has_backlinks());
+    assert_eq!(map.backlinks(2), None);
+    assert_eq!(map.in_degree(2), None);
+}
+
+#[test]
+fn backlinks() {
+    let links = vec![(1, 2), (1, 3), (3, 2)].into_iter().collect();
+
+    let mut map = LinkMap::new_with_progress(links, ProgressBuilder::empty());
+    map.build_backlinks(ProgressBuilder::empty());
+
+    assert!(map.has_backlinks());
+    assert_eq!(map.backlinks(1), None);
+    assert_eq!(map.backlinks(2), Some(&[1, 3][..]));
+    assert_eq!(map.backlinks(3), Some(&[1][..]));
+
+    assert_eq!(map.in_degree(1), Some(0));
+    assert_eq!(map.in_degree(2), Some(2));
+    assert_eq!(map.out_degree(1), 2);
+    assert_eq!(map.out_degree(2), 0);
+    assert_eq!(map.max_id(), 3);
+}
+
+#[test]
+fn update_link_map() {
+    let mut map = LinkMap::new_with_progress(
+        vec![(1, 2), (1, 3), (3, 2), (4, 1)].into_iter().collect(),
+        ProgressBuilder::empty(),
+    );
+    map.build_backlinks(ProgressBuilder::empty());
+    assert_eq!(map.max_id(), 4);
+
+    let update = map.update(
+        vec![(1, 3), (1, 2), (3, 5), (3, 5)].into_iter().collect(),
+        ProgressBuilder::empty(),
+    );
+
+    assert_eq!(
+        update,
+        LinkMapUpdate {
+            added: 2,
+            removed: 2,
+            changed_pages: 2,
+        }
+    );
+    assert_eq!(map.get(1), Some(&[2, 3][..]));
+    assert_eq!(map.get(3), Some(&[5, 5][..]));
+    assert_eq!(map.get(4), None);
+    assert_eq!(map.backlinks(1), None);
+    assert_eq!(map.backlinks(2), Some(&[1][..]));
+    assert_eq!(map.backlinks(5), Some(&[3, 3][..]));
+    assert_eq!(map.max_id(), 5);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn update_matches_new_map(
+        old in proptest::collection::vec((0..8, 0..8), 0..40),
+        new in proptest::collection::vec((0..8, 0..8), 0..40),
+    ) {
+        let mut map = LinkMap::new_with_progress(old.into_iter().collect(), ProgressBuilder::empty());
+        map.build_backlinks(ProgressBuilder::empty());
+        map.update(new.iter().copied().collect(), ProgressBuilder::empty());
+
+        let mut fresh = LinkMap::new_with_progress(new.into_iter().collect(), ProgressBuilder::empty());
+        fresh.build_backlinks(ProgressBuilder::empty());
+
+        proptest::prop_assert_eq!(&map.forward, &fresh.forward);
+        proptest::prop_assert_eq!(&map.backward, &fresh.backward);
+    }
 }
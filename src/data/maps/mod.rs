@@ -1,2 +1,6 @@
+pub mod adjacency;
+pub mod component_map;
+pub mod landmark_map;
 pub mod link_map;
 pub mod page_map;
+pub mod rank_map;
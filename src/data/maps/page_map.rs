@@ -1,9 +1,12 @@
 use crate::{
-    data::{pages::Page, redirects::Redirect},
+    data::{mapped::MappedFile, pages::Page, redirects::Redirect},
     indication::ProgressBuilder,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct PageMap {
@@ -13,6 +16,21 @@ pub struct PageMap {
     name_to_id: HashMap<String, i32>,
     // id -> redirect_id (if page is a redirect)
     id_to_redirect: HashMap<i32, i32>,
+    // id -> length of the wikitext in bytes
+    #[serde(default)]
+    id_to_length: HashMap<i32, u32>,
+    // set instead of the maps for databases in the mapped format
+    #[serde(skip)]
+    mapped: Option<Arc<MappedFile>>,
+}
+
+/// Changes applied by `PageMap::update`
+#[derive(Debug, Default, PartialEq)]
+pub struct PageMapUpdate {
+    pub added: usize,
+    pub removed: usize,
+    pub renamed: usize,
+    pub redirects_changed: usize,
 }
 
 #[derive(Debug, PartialEq)]
@@ -35,10 +53,12 @@ impl PageMap {
         let mut id_to_name = HashMap::new();
         let mut name_to_id = HashMap::new();
         let mut id_to_redirect = HashMap::new();
+        let mut id_to_length = HashMap::new();
 
         for page in pages {
             id_to_name.insert(page.id, page.title.clone());
             name_to_id.insert(page.title.clone(), page.id);
+            id_to_length.insert(page.id, page.length);
 
             progress.inc(1);
         }
@@ -59,6 +79,8 @@ impl PageMap {
             id_to_name,
             name_to_id,
             id_to_redirect,
+            id_to_length,
+            mapped: None,
         }
     }
 
@@ -70,16 +92,119 @@ impl PageMap {
         Self::new_internal(pages, redirect, progress)
     }
 
+    /// Patches the map to the pages and redirects of a newer dump, the result is the same as
+    /// building a new map from them
+    pub fn update(
+        &mut self,
+        pages: VecDeque<Page>,
+        redirects: VecDeque<Redirect>,
+        progress: ProgressBuilder,
+    ) -> PageMapUpdate {
+        assert!(self.mapped.is_none(), "mapped page maps are read-only");
+
+        let progress = progress
+            .with_len((pages.len() + redirects.len()) as u64)
+            .build();
+
+        let mut update = PageMapUpdate::default();
+        let mut ids = HashSet::with_capacity(pages.len());
+
+        for page in pages {
+            ids.insert(page.id);
+
+            match self.id_to_name.get(&page.id) {
+                Some(title) if *title == page.title => {}
+                old_title => {
+                    match old_title {
+                        // the old title may already belong to a page renamed before
+                        Some(old_title) => {
+                            update.renamed += 1;
+                            if self.name_to_id.get(old_title) == Some(&page.id) {
+                                self.name_to_id.remove(old_title);
+                            }
+                        }
+                        None => update.added += 1,
+                    }
+
+                    self.name_to_id.insert(page.title.clone(), page.id);
+                    self.id_to_name.insert(page.id, page.title);
+                }
+            }
+            self.id_to_length.insert(page.id, page.length);
+
+            progress.inc(1);
+        }
+
+        let removed = self
+            .id_to_name
+            .keys()
+            .filter(|id| !ids.contains(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for id in removed {
+            let title = self.id_to_name.remove(&id).unwrap();
+            if self.name_to_id.get(&title) == Some(&id) {
+                self.name_to_id.remove(&title);
+            }
+            self.id_to_length.remove(&id);
+            update.removed += 1;
+        }
+
+        let mut id_to_redirect = HashMap::with_capacity(redirects.len());
+        for redirect in redirects {
+            if let Some(&to) = self.name_to_id.get(&redirect.title) {
+                id_to_redirect.insert(redirect.id, to);
+            }
+
+            progress.inc(1);
+        }
+
+        self.id_to_redirect.retain(|from, _| {
+            let keep = id_to_redirect.contains_key(from);
+            update.redirects_changed += !keep as usize;
+            keep
+        });
+        for (from, to) in id_to_redirect {
+            if self.id_to_redirect.insert(from, to) != Some(to) {
+                update.redirects_changed += 1;
+            }
+        }
+
+        progress.finish();
+
+        update
+    }
+
+    /// Pages read from a memory-mapped database file
+    pub fn mapped(file: Arc<MappedFile>) -> Self {
+        Self {
+            id_to_name: HashMap::new(),
+            name_to_id: HashMap::new(),
+            id_to_redirect: HashMap::new(),
+            id_to_length: HashMap::new(),
+            mapped: Some(file),
+        }
+    }
+
     pub fn name_to_id(&self, name: &str) -> Option<i32> {
-        self.name_to_id.get(name).copied()
+        match &self.mapped {
+            Some(file) => file.id(name),
+            None => self.name_to_id.get(name).copied(),
+        }
     }
 
     pub fn id_to_name(&self, id: i32) -> Option<&str> {
-        self.id_to_name.get(&id).map(|s| s.as_str())
+        match &self.mapped {
+            Some(file) => file.title(id),
+            None => self.id_to_name.get(&id).map(|s| s.as_str()),
+        }
     }
 
     pub fn id_to_redirect(&self, id: i32) -> Option<i32> {
-        self.id_to_redirect.get(&id).copied()
+        match &self.mapped {
+            Some(file) => file.redirect(id),
+            None => self.id_to_redirect.get(&id).copied(),
+        }
     }
 
     pub fn lookup_title(&self, title: &str) -> Option<PageMapResult> {
@@ -102,6 +227,54 @@ impl PageMap {
         })
     }
 
+    /// All pages as `(id, title)`, including redirects
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (i32, &str)> + '_> {
+        match &self.mapped {
+            Some(file) => Box::new(file.iter_pages()),
+            None => Box::new(
+                self.id_to_name
+                    .iter()
+                    .map(|(&id, title)| (id, title.as_str())),
+            ),
+        }
+    }
+
+    /// All pages as `(title, id)` from the title lookup, the inverse of `iter`
+    pub fn titles(&self) -> Box<dyn Iterator<Item = (&str, i32)> + '_> {
+        match &self.mapped {
+            Some(file) => Box::new(file.iter_titles()),
+            None => Box::new(
+                self.name_to_id
+                    .iter()
+                    .map(|(title, &id)| (title.as_str(), id)),
+            ),
+        }
+    }
+
+    /// Length of the wikitext in bytes, `None` for unknown pages or databases derived before lengths were stored
+    pub fn length(&self, id: i32) -> Option<u32> {
+        match &self.mapped {
+            Some(file) => file.length(id),
+            None => self.id_to_length.get(&id).copied(),
+        }
+    }
+
+    /// All redirects as `(from, to)`
+    pub fn redirects(&self) -> Box<dyn Iterator<Item = (i32, i32)> + '_> {
+        match &self.mapped {
+            Some(file) => Box::new(file.iter_redirects()),
+            None => Box::new(self.id_to_redirect.iter().map(|(&from, &to)| (from, to))),
+        }
+    }
+
+    /// Number of pages, including redirects
+    pub fn len(&self) -> usize {
+        match &self.mapped {
+            Some(file) => file.page_count(),
+            None => self.id_to_name.len(),
+        }
+    }
+
     pub fn resolve_by_title(&self, title: &str) -> Option<PageMapResult> {
         let mut page = self.lookup_title(title)?;
         while let Some(redirect) = page.redirect {
@@ -119,16 +292,19 @@ fn new_page_map() {
                 id: 1,
                 title: "Page 1".to_string(),
                 redirect: false,
+                length: 100,
             },
             Page {
                 id: 2,
                 title: "Page 2".to_string(),
                 redirect: false,
+                length: 100,
             },
             Page {
                 id: 3,
                 title: "Also Page 2".to_string(),
                 redirect: true,
+                length: 100,
             },
         ];
         VecDeque::from(pages)
@@ -152,6 +328,9 @@ fn new_page_map() {
     assert_eq!(map.id_to_name(2), Some("Page 2"));
     assert_eq!(map.id_to_name(3), Some("Also Page 2"));
 
+    assert_eq!(map.length(1), Some(100));
+    assert_eq!(map.length(4), None);
+
     assert_eq!(map.id_to_redirect(1), None);
     assert_eq!(map.id_to_redirect(2), None);
     assert_eq!(map.id_to_redirect(3), Some(2));
@@ -233,3 +412,60 @@ fn new_page_map() {
         })
     );
 }
+
+#[test]
+fn update_page_map() {
+    let page = |id: i32, title: &str, length: u32| Page {
+        id,
+        title: title.to_string(),
+        redirect: false,
+        length,
+    };
+    let redirect = |id: i32, title: &str| Redirect {
+        id,
+        title: title.to_string(),
+    };
+
+    let mut map = PageMap::new_with_progress(
+        vec![
+            page(1, "Linux", 100),
+            page(2, "Torvalds", 100),
+            page(3, "Kernel", 100),
+            page(4, "Helsinki", 100),
+            page(5, "Linus_Torvalds", 100),
+        ]
+        .into(),
+        vec![redirect(5, "Torvalds"), redirect(3, "Linux")].into(),
+        ProgressBuilder::empty(),
+    );
+
+    // 2 and 5 swap titles (5 still redirects to 2), 3 is removed, 6 is added and 4 becomes a
+    // redirect
+    let pages = || {
+        VecDeque::from(vec![
+            page(1, "Linux", 200),
+            page(2, "Linus_Torvalds", 100),
+            page(4, "Helsinki", 100),
+            page(5, "Torvalds", 100),
+            page(6, "Finnland", 100),
+        ])
+    };
+    let redirects = || VecDeque::from(vec![redirect(5, "Linus_Torvalds"), redirect(4, "Finnland")]);
+
+    let update = map.update(pages(), redirects(), ProgressBuilder::empty());
+    let fresh = PageMap::new_with_progress(pages(), redirects(), ProgressBuilder::empty());
+
+    assert_eq!(
+        update,
+        PageMapUpdate {
+            added: 1,
+            removed: 1,
+            renamed: 2,
+            redirects_changed: 2,
+        }
+    );
+    assert_eq!(map.id_to_name, fresh.id_to_name);
+    assert_eq!(map.name_to_id, fresh.name_to_id);
+    assert_eq!(map.id_to_redirect, fresh.id_to_redirect);
+    assert_eq!(map.id_to_length, fresh.id_to_length);
+}
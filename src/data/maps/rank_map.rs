@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Importance score per page, e.g. the PageRank
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RankMap {
+    // page -> score
+    ranks: HashMap<i32, f64>,
+}
+
+impl RankMap {
+    pub fn new(ranks: HashMap<i32, f64>) -> Self {
+        Self { ranks }
+    }
+
+    pub fn get(&self, id: i32) -> Option<f64> {
+        self.ranks.get(&id).copied()
+    }
+
+    /// The `n` pages with the highest scores, highest first
+    pub fn top(&self, n: usize) -> Vec<(i32, f64)> {
+        let mut ranks = self
+            .ranks
+            .iter()
+            .map(|(&id, &rank)| (id, rank))
+            .collect::<Vec<_>>();
+        ranks.sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranks.truncate(n);
+
+        ranks
+    }
+}
+
+#[test]
+fn top_ranks() {
+    let map = RankMap::new([(1, 0.2), (2, 0.5), (3, 0.1), (4, 0.2)].into());
+
+    assert_eq!(map.top(3), vec![(2, 0.5), (1, 0.2), (4, 0.2)]);
+    assert_eq!(map.top(10).len(), 4);
+}
@@ -3,6 +3,10 @@ pub mod maps;
 pub mod parsers;
 
 pub mod database;
+pub mod diff;
 pub mod links;
+pub mod mapped;
 pub mod pages;
 pub mod redirects;
+pub mod stats;
+pub mod verify;
@@ -2,5 +2,8 @@
 pub struct Page {
     pub id: i32,
     pub title: String,
+    #[allow(dead_code)] // redirects are resolved through the redirect table
     pub redirect: bool,
+    /// Size of the wikitext in bytes
+    pub length: u32,
 }
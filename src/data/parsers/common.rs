@@ -3,29 +3,43 @@ use std::collections::VecDeque;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
-use crate::indication;
+use crate::{
+    error::{Error, Result},
+    indication,
+};
 
-pub fn get_file_line_count(file: &str) -> u64 {
+pub fn get_file_line_count(file: &str) -> Result<u64> {
     let spinner = indication::spinner(true); // with progress, should fit the other progress bars
     spinner.set_message(format!("Loading line count for {file}"));
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let file_ptr = File::open(file).expect("Unable to open file");
+    let file_ptr = File::open(file).map_err(Error::io(file))?;
     let reader = BufReader::new(file_ptr);
 
-    let count = reader.lines().count() as u64;
+    let mut count = 0;
+    for line in reader.lines() {
+        line.map_err(Error::io(file))?;
+        count += 1;
+    }
 
     spinner.finish_and_clear();
 
-    count
+    Ok(count)
+}
+
+/// Parses a numeric column of a dump line
+pub fn parse_number<T: std::str::FromStr>(value: &str, column: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| Error::Input(format!("Invalid {column} in dump: {value}")))
 }
 
 pub fn parse_file_async<R, C>(
     file: String,
     threads: i32,
-    line_handler: fn(String, C) -> Vec<R>,
+    line_handler: fn(String, C) -> Result<Vec<R>>,
     context: C,
-) -> VecDeque<R>
+) -> Result<VecDeque<R>>
 where
     R: Send,
     R: Sized,
@@ -33,23 +47,31 @@ where
     C: Send,
 {
     if threads < 2 {
-        panic!("Threads must be greater than or equal 2");
+        return Err(Error::Input(format!(
+            "Threads must be greater than or equal 2, got {threads}"
+        )));
     }
 
+    let file_ptr = File::open(&file).map_err(Error::io(&file))?;
     let (tx, rx) = crossbeam_channel::bounded(0);
 
-    let reader_thread = std::thread::spawn(move || {
-        let file_ptr = File::open(file).expect("Unable to open file");
+    // stops at the first read error, dropping the sender lets the parser threads finish. The
+    // parser threads stop at the first parse error, which makes sending fail
+    let reader_thread = std::thread::spawn(move || -> Result<()> {
         let reader = BufReader::new(file_ptr);
 
         for (i, line) in reader.lines().enumerate() {
-            let line = line.unwrap();
-            tx.send(line).expect("Error sending line");
+            let line = line.map_err(Error::io(&file))?;
+            if tx.send(line).is_err() {
+                break;
+            }
 
             if i % 100 == 0 {
                 debug!("Read {} lines", i);
             }
         }
+
+        Ok(())
     });
 
     let output = std::thread::scope(|s| {
@@ -59,13 +81,13 @@ where
             let rx = rx.clone();
             let context = context.clone();
 
-            let thread = s.spawn(move || {
+            let thread = s.spawn(move || -> Result<Vec<R>> {
                 let mut out = vec![];
 
                 let mut i = 0;
 
                 while let Ok(res) = rx.recv() {
-                    let res = line_handler(res, context.clone());
+                    let res = line_handler(res, context.clone())?;
                     out.extend(res);
 
                     if i % 100 == 0 {
@@ -74,21 +96,23 @@ where
                     i += 1;
                 }
 
-                out
+                Ok(out)
             });
 
             thread_handles.push(thread);
         }
+        // only the parser threads hold receivers, so sending fails once they all stopped
+        drop(rx);
 
         let mut outputs = VecDeque::new();
         for handle in thread_handles {
-            outputs.extend(handle.join().expect("Error joining thread"));
+            outputs.extend(handle.join().expect("Error joining thread")?);
         }
 
-        outputs
+        Ok(outputs)
     });
 
-    reader_thread.join().expect("Error joining thread");
+    reader_thread.join().expect("Error joining thread")?;
 
     output
 }
@@ -115,7 +139,7 @@ mod test {
                 let _file = std::fs::File::create(&file_path).expect("Unable to create file");
             }
 
-            let count = super::super::get_file_line_count(file_path.to_str().unwrap());
+            let count = super::super::get_file_line_count(file_path.to_str().unwrap()).unwrap();
 
             assert_eq!(count, 0);
         }
@@ -131,7 +155,7 @@ mod test {
                 writeln!(file, "test").expect("Unable to write to file");
             }
 
-            let count = super::super::get_file_line_count(file_path.to_str().unwrap());
+            let count = super::super::get_file_line_count(file_path.to_str().unwrap()).unwrap();
 
             assert_eq!(count, 1);
         }
@@ -151,12 +175,50 @@ mod test {
                 file.flush().expect("Unable to flush file");
             }
 
-            let count = super::super::get_file_line_count(file_path.to_str().unwrap());
+            let count = super::super::get_file_line_count(file_path.to_str().unwrap()).unwrap();
 
             assert_eq!(count, 1000);
         }
     }
 
+    #[test]
+    fn missing_file() {
+        let file_path = temp_dir().join("missing_dump.sql");
+
+        let result = super::parse_file_async(
+            file_path.to_str().unwrap().to_string(),
+            2,
+            |line, _| Ok(vec![line]),
+            (),
+        );
+
+        assert!(matches!(result, Err(crate::error::Error::Io { .. })));
+    }
+
+    #[test]
+    fn errors_are_returned() {
+        let file_path = temp_dir().join("test_errors.txt");
+        {
+            let file = std::fs::File::create(&file_path).expect("Unable to create file");
+            let mut writer = std::io::BufWriter::new(file);
+            for i in 0..1000 {
+                writeln!(writer, "{}", i).expect("Unable to write to file");
+            }
+        }
+        let file_path = file_path.to_str().unwrap().to_string();
+
+        let parse = |line: String, _| match line.as_str() {
+            "500" => Err(crate::error::Error::Input("Invalid line".to_string())),
+            _ => Ok(vec![line]),
+        };
+
+        let result = super::parse_file_async(file_path.clone(), 3, parse, ());
+        assert!(matches!(result, Err(crate::error::Error::Input(_))));
+
+        let result = super::parse_file_async(file_path, 1, parse, ());
+        assert!(matches!(result, Err(crate::error::Error::Input(_))));
+    }
+
     #[test]
     fn all_lines_are_read() {
         let dir = temp_dir();
@@ -179,10 +241,11 @@ mod test {
             2,
             |line, ctx| {
                 *ctx.lock().unwrap() += 1;
-                vec![line.parse::<i32>().unwrap()]
+                Ok(vec![line.parse::<i32>().unwrap()])
             },
             call_count.clone(),
-        );
+        )
+        .unwrap();
 
         // check that all lines are read
         assert_eq!(result.len(), 1000);
@@ -4,39 +4,44 @@ use regex::Regex;
 
 use crate::{
     data::{pages::Page, parsers::common},
+    error::Result,
     indication::{ProgressBuilder, ProgressReporter},
 };
 
-fn parse_page_entry(line: String, (re, progressbar): (Regex, &ProgressReporter)) -> Vec<Page> {
+fn parse_page_entry(
+    line: String,
+    (re, progressbar): (Regex, &ProgressReporter),
+) -> Result<Vec<Page>> {
     let mut out = vec![];
 
     for cap in re.captures_iter(&line) {
         out.push(Page {
-            id: cap[1].parse::<i32>().expect("Invalid id"),
+            id: common::parse_number(&cap[1], "page id")?,
             title: cap[2].to_string(),
-            redirect: cap[3].parse::<i32>().expect("Invalid redirect") != 0,
+            redirect: &cap[3] != "0",
+            length: common::parse_number(&cap[4], "page length")?,
         })
     }
 
     progressbar.inc(1);
 
-    out
+    Ok(out)
 }
 
 pub fn read_and_parse_pages(
     path: String,
     threads: i32,
     progress: ProgressBuilder,
-) -> VecDeque<Page> {
-    let re = Regex::new(r"\(([0-9]+),0,'([^']+)',([01]),[01],[0-9.]+,'[^']*','[^']*',[0-9]*,[0-9]+,'[^']*',[^\)]*\)").expect("Invalid regex");
+) -> Result<VecDeque<Page>> {
+    let re = Regex::new(r"\(([0-9]+),0,'([^']+)',([01]),[01],[0-9.]+,'[^']*','[^']*',[0-9]*,([0-9]+),'[^']*',[^\)]*\)").expect("Invalid regex");
 
     let progress = progress
-        .with_len(common::get_file_line_count(&path))
+        .with_len(common::get_file_line_count(&path)?)
         .build();
 
-    let out = common::parse_file_async(path, threads, parse_page_entry, (re, &progress));
+    let out = common::parse_file_async(path, threads, parse_page_entry, (re, &progress))?;
 
     progress.finish();
 
-    out
+    Ok(out)
 }
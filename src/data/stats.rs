@@ -0,0 +1,268 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::data::database::Database;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DegreeBucket {
+    pub min: usize,
+    pub max: usize,
+    pub pages: usize,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PageDegree {
+    pub id: i32,
+    pub title: String,
+    pub degree: usize,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ComponentStats {
+    pub count: usize,
+    pub largest: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphStats {
+    /// All pages, including redirects
+    pub pages: usize,
+    pub redirects: usize,
+    pub links: usize,
+    /// Links to pages that are not in the page map
+    pub dangling_links: usize,
+    /// Pages (excluding redirects) without outgoing links
+    pub pages_without_links: usize,
+    pub self_loops: usize,
+    /// Links that occur more than once on the same page, counting each repetition
+    pub duplicate_links: usize,
+    pub out_degree: Vec<DegreeBucket>,
+    pub in_degree: Vec<DegreeBucket>,
+    pub top_out_degree: Vec<PageDegree>,
+    pub top_in_degree: Vec<PageDegree>,
+    /// Number of redirects to follow until reaching a page -> number of redirects
+    pub redirect_chains: BTreeMap<usize, usize>,
+    /// Redirects that never reach a page that is not a redirect
+    pub redirect_cycles: usize,
+    pub components: Option<ComponentStats>,
+}
+
+/// Histogram with buckets 0, 1, 2-3, 4-7, ...
+fn degree_histogram(degrees: impl Iterator<Item = usize>) -> Vec<DegreeBucket> {
+    let mut buckets: Vec<DegreeBucket> = vec![];
+    for degree in degrees {
+        let bucket = (usize::BITS - degree.leading_zeros()) as usize;
+        while buckets.len() <= bucket {
+            let min = match buckets.len() {
+                0 => 0,
+                i => 1 << (i - 1),
+            };
+            buckets.push(DegreeBucket {
+                min,
+                max: (min * 2).max(1) - 1,
+                pages: 0,
+            });
+        }
+        buckets[bucket].pages += 1;
+    }
+
+    buckets
+}
+
+fn top_degrees(degrees: &HashMap<i32, usize>, n: usize, db: &Database) -> Vec<PageDegree> {
+    let mut top = degrees
+        .iter()
+        .map(|(&id, &degree)| (id, degree))
+        .collect::<Vec<_>>();
+    top.sort_unstable_by_key(|&(id, degree)| (std::cmp::Reverse(degree), id));
+
+    top.into_iter()
+        .take(n)
+        .map(|(id, degree)| PageDegree {
+            id,
+            title: db.pages.id_to_name(id).unwrap_or("?").to_string(),
+            degree,
+        })
+        .collect()
+}
+
+impl GraphStats {
+    /// Collects the statistics, listing the `top` pages with the highest degrees
+    pub fn new(db: &Database, top: usize) -> Self {
+        let mut out_degree: HashMap<i32, usize> = HashMap::new();
+        let mut in_degree: HashMap<i32, usize> = HashMap::new();
+
+        let (mut links, mut dangling_links, mut self_loops, mut duplicate_links) = (0, 0, 0, 0);
+
+        for (from, targets) in db.links.iter() {
+            out_degree.insert(from, targets.len());
+            links += targets.len();
+
+            let mut seen = HashSet::new();
+            for &to in targets {
+                *in_degree.entry(to).or_default() += 1;
+
+                if db.pages.id_to_name(to).is_none() {
+                    dangling_links += 1;
+                }
+                if from == to {
+                    self_loops += 1;
+                }
+                if !seen.insert(to) {
+                    duplicate_links += 1;
+                }
+            }
+        }
+
+        let pages_without_links = db
+            .pages
+            .iter()
+            .filter(|&(id, _)| db.pages.id_to_redirect(id).is_none())
+            .filter(|&(id, _)| !out_degree.contains_key(&id))
+            .count();
+
+        let mut redirect_chains = BTreeMap::new();
+        let mut redirect_cycles = 0;
+        for (from, _) in db.pages.redirects() {
+            let mut visited = HashSet::from([from]);
+            let mut at = from;
+            let mut cycle = false;
+            while let Some(next) = db.pages.id_to_redirect(at) {
+                if !visited.insert(next) {
+                    cycle = true;
+                    break;
+                }
+                at = next;
+            }
+
+            if cycle {
+                redirect_cycles += 1;
+            } else {
+                *redirect_chains.entry(visited.len() - 1).or_default() += 1;
+            }
+        }
+
+        let all_pages = || db.pages.iter().map(|(id, _)| id);
+
+        Self {
+            pages: db.pages.len(),
+            redirects: db.pages.redirects().count(),
+            links,
+            dangling_links,
+            pages_without_links,
+            self_loops,
+            duplicate_links,
+            out_degree: degree_histogram(
+                all_pages().map(|id| out_degree.get(&id).copied().unwrap_or(0)),
+            ),
+            in_degree: degree_histogram(
+                all_pages().map(|id| in_degree.get(&id).copied().unwrap_or(0)),
+            ),
+            top_out_degree: top_degrees(&out_degree, top, db),
+            top_in_degree: top_degrees(&in_degree, top, db),
+            redirect_chains,
+            redirect_cycles,
+            components: db.components.as_ref().map(|components| ComponentStats {
+                count: components.len(),
+                largest: components.largest(),
+            }),
+        }
+    }
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::{DegreeBucket, GraphStats};
+    #[allow(unused_imports)]
+    use crate::{
+        data::{
+            database::Database,
+            maps::{link_map::LinkMap, page_map::PageMap},
+            pages::Page,
+            redirects::Redirect,
+        },
+        indication::ProgressBuilder,
+    };
+
+    #[test]
+    fn stats() {
+        let pages = (1..=5)
+            .map(|id| Page {
+                id,
+                title: format!("Page {id}"),
+                redirect: id >= 4,
+                length: 100,
+            })
+            .collect();
+        // 4 redirects to 5, which redirects to 1
+        let redirects = vec![
+            Redirect {
+                id: 4,
+                title: "Page 5".to_string(),
+            },
+            Redirect {
+                id: 5,
+                title: "Page 1".to_string(),
+            },
+        ]
+        .into();
+        let pages = PageMap::new_with_progress(pages, redirects, ProgressBuilder::empty());
+
+        let links = LinkMap::new_with_progress(
+            vec![
+                (1, 2),
+                (1, 2),
+                (1, 3),
+                (2, 2),
+                (3, 1),
+                (3, 9),
+                (4, 5),
+                (5, 1),
+            ]
+            .into_iter()
+            .collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let stats = GraphStats::new(&Database::new(links, pages), 2);
+
+        assert_eq!(stats.pages, 5);
+        assert_eq!(stats.redirects, 2);
+        assert_eq!(stats.links, 8);
+        assert_eq!(stats.dangling_links, 1);
+        assert_eq!(stats.pages_without_links, 0);
+        assert_eq!(stats.self_loops, 1);
+        assert_eq!(stats.duplicate_links, 1);
+        assert_eq!(stats.redirect_chains, [(1, 1), (2, 1)].into());
+        assert_eq!(stats.redirect_cycles, 0);
+        assert_eq!(stats.components, None);
+
+        assert_eq!(
+            stats.out_degree,
+            vec![
+                DegreeBucket {
+                    min: 0,
+                    max: 0,
+                    pages: 0
+                },
+                DegreeBucket {
+                    min: 1,
+                    max: 1,
+                    pages: 3
+                },
+                DegreeBucket {
+                    min: 2,
+                    max: 3,
+                    pages: 2
+                },
+            ]
+        );
+
+        assert_eq!(stats.top_out_degree[0].title, "Page 1");
+        assert_eq!(stats.top_out_degree[0].degree, 3);
+        assert_eq!(stats.top_in_degree[0].title, "Page 2");
+        assert_eq!(stats.top_in_degree[0].degree, 3);
+        assert_eq!(stats.top_in_degree.len(), 2);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use crate::data::database::Database;
+
+/// Outcome of checking one invariant of a database
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    /// Number of links, pages or redirects violating the invariant
+    pub violations: usize,
+    /// Descriptions of the first violations
+    pub examples: Vec<String>,
+}
+
+impl Check {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            violations: 0,
+            examples: vec![],
+        }
+    }
+
+    fn violation(&mut self, max_examples: usize, example: impl FnOnce() -> String) {
+        self.violations += 1;
+        if self.examples.len() < max_examples {
+            self.examples.push(example());
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.violations == 0
+    }
+}
+
+/// Checks the structural invariants of the links and pages, listing up to `max_examples`
+/// violations per check
+pub fn check(db: &Database, max_examples: usize) -> Vec<Check> {
+    let name = |id: i32| {
+        db.pages
+            .id_to_name(id)
+            .map_or_else(|| format!("#{id}"), |title| title.to_string())
+    };
+
+    let mut sections = Check::new("Sections are consistent");
+    if let Some(Err(error)) = db.links.mapped_file().map(|file| file.check_rows()) {
+        sections.violation(max_examples, || error.to_string());
+    }
+
+    let mut link_targets = Check::new("Link targets are pages");
+    for (from, targets) in db.links.iter() {
+        for &to in targets {
+            if db.pages.id_to_name(to).is_none() {
+                link_targets.violation(max_examples, || format!("{} -> #{to}", name(from)));
+            }
+        }
+    }
+
+    let mut redirect_targets = Check::new("Redirect targets are pages");
+    for (from, to) in db.pages.redirects() {
+        if db.pages.id_to_name(to).is_none() {
+            redirect_targets.violation(max_examples, || format!("{} -> #{to}", name(from)));
+        }
+    }
+
+    let mut inverse = Check::new("Titles and ids are inverse");
+    for (id, title) in db.pages.iter() {
+        let found = db.pages.name_to_id(title);
+        if found != Some(id) {
+            inverse.violation(max_examples, || {
+                format!("#{id} is {title}, but {title} is {found:?}")
+            });
+        }
+    }
+    for (title, id) in db.pages.titles() {
+        let found = db.pages.id_to_name(id);
+        if found != Some(title) {
+            inverse.violation(max_examples, || {
+                format!("{title} is #{id}, but #{id} is {found:?}")
+            });
+        }
+    }
+
+    let mut redirect_cycles = Check::new("No redirect cycles");
+    for (from, _) in db.pages.redirects() {
+        let mut visited = HashSet::from([from]);
+        let mut at = from;
+        while let Some(next) = db.pages.id_to_redirect(at) {
+            if !visited.insert(next) {
+                redirect_cycles
+                    .violation(max_examples, || format!("{} -> {}", name(from), name(next)));
+                break;
+            }
+            at = next;
+        }
+    }
+
+    vec![
+        sections,
+        link_targets,
+        redirect_targets,
+        inverse,
+        redirect_cycles,
+    ]
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::check;
+    #[allow(unused_imports)]
+    use crate::{
+        data::{
+            database::Database,
+            maps::{link_map::LinkMap, page_map::PageMap},
+            pages::Page,
+            redirects::Redirect,
+        },
+        indication::ProgressBuilder,
+    };
+
+    #[test]
+    fn violations() {
+        // 3 and 6 share a title, 4 and 5 redirect to each other
+        let pages = (1..=6)
+            .map(|id| Page {
+                id,
+                title: format!("Page {}", if id == 6 { 3 } else { id }),
+                redirect: id == 4 || id == 5,
+                length: 100,
+            })
+            .collect();
+        let redirects = vec![
+            Redirect {
+                id: 4,
+                title: "Page 5".to_string(),
+            },
+            Redirect {
+                id: 5,
+                title: "Page 4".to_string(),
+            },
+        ]
+        .into();
+        let pages = PageMap::new_with_progress(pages, redirects, ProgressBuilder::empty());
+        let links = LinkMap::new_with_progress(
+            vec![(1, 2), (1, 9), (2, 1)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        let checks = check(&Database::new(links, pages), 5);
+        let violations = checks
+            .iter()
+            .map(|check| (check.name, check.violations))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            violations,
+            vec![
+                ("Sections are consistent", 0),
+                ("Link targets are pages", 1),
+                ("Redirect targets are pages", 0),
+                ("Titles and ids are inverse", 1),
+                ("No redirect cycles", 2),
+            ]
+        );
+        assert_eq!(checks[1].examples, vec!["Page 1 -> #9"]);
+    }
+
+    #[test]
+    fn consistent_database() {
+        let pages = (1..=3)
+            .map(|id| Page {
+                id,
+                title: format!("Page {id}"),
+                redirect: id == 3,
+                length: 100,
+            })
+            .collect();
+        let redirects = vec![Redirect {
+            id: 3,
+            title: "Page 1".to_string(),
+        }]
+        .into();
+        let pages = PageMap::new_with_progress(pages, redirects, ProgressBuilder::empty());
+        let links = LinkMap::new_with_progress(
+            vec![(1, 2), (2, 3)].into_iter().collect(),
+            ProgressBuilder::empty(),
+        );
+
+        assert!(check(&Database::new(links, pages), 5)
+            .iter()
+            .all(|check| check.passed()));
+    }
+}
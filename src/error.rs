@@ -0,0 +1,81 @@
+use thiserror::Error;
+
+/// Errors reading or writing databases and dumps, and invalid command input
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error accessing {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Not a database file or written by a version without file headers, derive it again")]
+    NotADatabase,
+
+    #[error(
+        "Database has format version {found}, but this version reads {expected}, derive it again"
+    )]
+    Version { found: u32, expected: u32 },
+
+    #[error("Database is corrupt: {0}")]
+    Corrupt(String),
+
+    /// Input the command can not work with, e.g. an unknown page or a missing database feature
+    #[error("{0}")]
+    Input(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Wraps an I/O error on `path`, for use with `map_err`
+    pub fn io(path: &str) -> impl FnOnce(std::io::Error) -> Error + '_ {
+        move |source| Error::Io {
+            path: path.to_string(),
+            source,
+        }
+    }
+
+    /// Exit code of the process when a command fails with this error. 1 is left to panics and 2
+    /// to clap for invalid arguments
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Input(_) => 3,
+            Error::Io { .. } => 4,
+            Error::NotADatabase => 5,
+            Error::Version { .. } => 6,
+            Error::Corrupt(_) => 7,
+        }
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Input(message.to_string())
+    }
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::Error;
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let errors = [
+            Error::Input("Page not found".to_string()),
+            Error::io("db")(std::io::ErrorKind::NotFound.into()),
+            Error::NotADatabase,
+            Error::Version {
+                found: 1,
+                expected: 2,
+            },
+            Error::Corrupt("truncated".to_string()),
+        ];
+
+        let mut codes = errors.iter().map(Error::exit_code).collect::<Vec<_>>();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|&code| code > 2));
+    }
+}
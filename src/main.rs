@@ -2,6 +2,7 @@ use clap::Parser;
 
 mod commands;
 mod data;
+mod error;
 mod indication;
 
 #[derive(Parser, Debug)]
@@ -16,7 +17,12 @@ fn main() {
     let args = Args::parse();
 
     match args.command {
-        Some(command) => command.execute(),
+        Some(command) => {
+            if let Err(error) = command.execute() {
+                eprintln!("{error}");
+                std::process::exit(error.exit_code());
+            }
+        }
         None => {
             println!("No command specified");
         }